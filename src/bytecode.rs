@@ -0,0 +1,299 @@
+use crate::context::Context;
+use crate::error::Diagnostic;
+use crate::item::Function;
+use crate::node::{self, Node};
+use crate::span::{Span, Spanned};
+use crate::token::Operator;
+use crate::value::Value;
+
+#[derive(Debug, Clone)]
+enum Instruction {
+	Constant(f64),
+	/// Pushes the value of the variable in a slot.
+	Load(usize),
+	/// Evaluates a term with the tree walk. Terms that fail to
+	/// resolve when the program is compiled are evaluated
+	/// when reached so that their errors are reported in order.
+	Evaluate(usize),
+	/// Applies the function at an index of the function table
+	/// to the arguments at the top of the stack.
+	Function(usize),
+	Operator(Spanned<Operator>, Span, Span),
+	/// Replaces the left operand of a logical operator with the
+	/// result and jumps if the right operand need not be evaluated.
+	Short(Operator, usize),
+	/// Pops a condition and jumps if the condition is zero.
+	Branch(usize),
+	Jump(usize),
+}
+
+/// A node compiled into instructions for a stack machine. Programs
+/// evaluate numbers only and the variables of a program are held in
+/// slots that are given whenever the program is run. Errors are those
+/// of the tree walk as both share the implementation of each function
+/// and operator.
+#[derive(Debug)]
+pub struct Program {
+	instructions: Vec<Instruction>,
+	functions: Vec<(Spanned<Function>, Vec<Span>)>,
+	nodes: Vec<Spanned<Node>>,
+	depth: usize,
+}
+
+impl Program {
+	/// Compiles a node with the identifiers held in each slot. The
+	/// variables and identifiers bound by the context are resolved
+	/// once as they cannot change while the program is run. Returns
+	/// `None` if the node contains terms that may not be numbers such
+	/// as lists, or terms that bind variables such as series.
+	pub fn compile(node: &Spanned<Node>, slots: &[String], context: &Context) -> Option<Program> {
+		let mut compiler = Compiler {
+			program: Program {
+				instructions: Vec::new(),
+				functions: Vec::new(),
+				nodes: Vec::new(),
+				depth: 0,
+			},
+			slots,
+			context,
+			depth: 0,
+		};
+
+		compiler.node(node)?;
+		Some(compiler.program)
+	}
+
+	/// Runs the program with the values of the variables in each slot.
+	pub fn run(&self, context: &Context, slots: &[f64]) -> Result<f64, Diagnostic> {
+		let mut stack = Vec::with_capacity(self.depth);
+		let mut index = 0;
+		while let Some(instruction) = self.instructions.get(index) {
+			index += 1;
+			match instruction {
+				Instruction::Constant(value) => stack.push(*value),
+				Instruction::Load(slot) => stack.push(slots[*slot]),
+				Instruction::Evaluate(node) => stack.push(self.nodes[*node].number(context)?),
+				Instruction::Function(function) => {
+					let (function, spans) = &self.functions[*function];
+					let start = stack.len() - spans.len();
+					let value = node::function(context, function, spans, &stack[start..])?;
+					stack.truncate(start);
+					stack.push(value);
+				}
+				Instruction::Operator(operator, left_span, right_span) => {
+					let right = stack.pop().unwrap();
+					let left = stack.last_mut().unwrap();
+					*left = node::operator(context, operator, (*left_span, *left), (*right_span, right))?;
+				}
+				Instruction::Short(operator, target) => {
+					let left = stack.last_mut().unwrap();
+					match operator {
+						Operator::And if *left == 0.0 => *left = 0.0,
+						Operator::Or if *left != 0.0 => *left = 1.0,
+						_ => continue,
+					}
+					index = *target;
+				}
+				Instruction::Branch(target) => if stack.pop().unwrap() == 0.0 {
+					index = *target;
+				},
+				Instruction::Jump(target) => index = *target,
+			}
+		}
+		Ok(stack.pop().unwrap())
+	}
+}
+
+/// Evaluates a node as a function of a variable with a compiled
+/// program if the node can be compiled and with the tree walk
+/// otherwise.
+pub fn function<'a>(node: &'a Spanned<Node>, variable: &'a str, context: &'a Context)
+                    -> impl Fn(f64) -> Result<f64, Diagnostic> + 'a {
+	let program = Program::compile(node, &[variable.to_owned()], context);
+	move |value| match &program {
+		Some(program) => program.run(context, &[value]),
+		None => context.bind(variable, value, || node.number(context)),
+	}
+}
+
+struct Compiler<'a> {
+	program: Program,
+	slots: &'a [String],
+	context: &'a Context,
+	depth: usize,
+}
+
+impl Compiler<'_> {
+	fn node(&mut self, node: &Spanned<Node>) -> Option<()> {
+		match &node.node {
+			Node::Terminal(terminal) => self.push(Instruction::Constant(*terminal)),
+			Node::Variable(_) => match node.evaluate(self.context) {
+				Ok(Value::Number(value) | Value::Estimate(value, _)) =>
+					self.push(Instruction::Constant(value)),
+				Ok(_) => return None,
+				Err(_) => self.evaluate(node),
+			},
+			Node::Identifier(identifier) => {
+				let slot = self.slots.iter().rposition(|slot| slot == identifier);
+				match (slot, self.context.binding(identifier)) {
+					(Some(slot), _) => self.push(Instruction::Load(slot)),
+					(None, Some(value)) => self.push(Instruction::Constant(value)),
+					(None, None) => self.evaluate(node),
+				}
+			}
+			Node::Function(function, arguments) => match function.node {
+				Function::Statistic(_) | Function::Series(_, _) | Function::Calculus(_, _) |
+				Function::Roots | Function::Linear(_) => return None,
+				_ => {
+					for argument in arguments {
+						self.node(argument)?;
+					}
+
+					let spans = arguments.iter().map(|argument| argument.span).collect();
+					self.program.functions.push((function.clone(), spans));
+					self.depth -= arguments.len();
+					self.push(Instruction::Function(self.program.functions.len() - 1));
+				}
+			},
+			Node::Operator(operator, left, right) => {
+				self.node(left)?;
+				let short = match operator.node {
+					Operator::And | Operator::Or => {
+						self.program.instructions.push(Instruction::Short(operator.node, 0));
+						Some(self.program.instructions.len() - 1)
+					}
+					_ => None,
+				};
+
+				self.node(right)?;
+				self.depth -= 1;
+				self.program.instructions.push(Instruction::Operator(*operator, left.span, right.span));
+				if let Some(short) = short {
+					self.program.instructions[short] =
+						Instruction::Short(operator.node, self.program.instructions.len());
+				}
+			}
+			Node::Conditional(condition, consequent, alternative) => {
+				self.node(condition)?;
+				self.depth -= 1;
+				let branch = self.program.instructions.len();
+				self.program.instructions.push(Instruction::Branch(0));
+
+				self.node(consequent)?;
+				self.depth -= 1;
+				let jump = self.program.instructions.len();
+				self.program.instructions.push(Instruction::Jump(0));
+
+				self.program.instructions[branch] = Instruction::Branch(jump + 1);
+				self.node(alternative)?;
+				self.program.instructions[jump] = Instruction::Jump(self.program.instructions.len());
+			}
+			Node::List(_) | Node::Range(_, _) => return None,
+		}
+		Some(())
+	}
+
+	fn evaluate(&mut self, node: &Spanned<Node>) {
+		self.program.nodes.push(node.clone());
+		self.push(Instruction::Evaluate(self.program.nodes.len() - 1));
+	}
+
+	/// Pushes an instruction that leaves one more value on the stack.
+	fn push(&mut self, instruction: Instruction) {
+		self.program.instructions.push(instruction);
+		self.depth += 1;
+		self.program.depth = self.program.depth.max(self.depth);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::error::Error;
+	use crate::tests::parse;
+
+	/// Compares the result and error of a program with the tree walk.
+	fn compare(context: &Context, expression: &str, value: f64) -> Result<f64, Error> {
+		let node = parse(expression, &["x"]);
+		let program = Program::compile(&node, &["x".to_owned()], context).unwrap();
+		let tree = context.bind("x", value, || node.number(context));
+		let compiled = program.run(context, &[value]);
+		match (tree, compiled) {
+			(Ok(tree), Ok(compiled)) => {
+				assert!(tree == compiled || (tree.is_nan() && compiled.is_nan()), "{}", expression);
+				Ok(compiled)
+			}
+			(Err(tree), Err(compiled)) => {
+				assert_eq!(tree.error, compiled.error, "{}", expression);
+				assert_eq!(tree.secondary, compiled.secondary, "{}", expression);
+				Err(compiled.error.node)
+			}
+			(tree, compiled) => panic!("{}: {:?} and {:?}", expression, tree, compiled),
+		}
+	}
+
+	#[test]
+	fn test_program() {
+		let context = &mut Context::default();
+		context.push_value(Value::Number(2.0));
+		assert_eq!(compare(context, "x ^ 2 + 3 * x - 1", 2.0), Ok(9.0));
+		assert_eq!(compare(context, "sin x + cos x ^ 2 * $", 0.0), Ok(2.0));
+		assert_eq!(compare(context, "ncr x 2 + gcd 12 x", 4.0), Ok(10.0));
+		assert_eq!(compare(context, "if x < 0 then -x else x ^ 3", -2.0), Ok(2.0));
+		assert_eq!(compare(context, "if x < 0 then -x else x ^ 3", 2.0), Ok(8.0));
+		assert_eq!(compare(context, "x > 1 and 1 / (x - 2) or 0", 1.0), Ok(0.0));
+		assert_eq!(compare(context, "x == 1 or 1 / 0", 1.0), Ok(1.0));
+	}
+
+	#[test]
+	fn test_errors() {
+		let context = &mut Context::default();
+		assert_eq!(compare(context, "1 / (x - 1)", 1.0), Err(Error::ZeroDivision));
+		assert_eq!(compare(context, "sqrt x + 1", -1.0), Err(Error::NegativeRoot));
+		assert_eq!(compare(context, "ncr x 2", 1.5), Err(Error::NonInteger));
+		assert_eq!(compare(context, "if x then $ else 1", 1.0), Err(Error::InvalidEvaluationOffset));
+		assert_eq!(compare(context, "if x then $ else 1", 0.0), Ok(1.0));
+		assert_eq!(compare(context, "10 ^ x", 400.0), Err(Error::Overflow));
+		context.ieee = true;
+		assert!(compare(context, "1 / (x - 1)", 1.0).unwrap().is_infinite());
+	}
+
+	#[test]
+	fn test_uncompiled() {
+		let context = &mut Context::default();
+		let compile = |expression| Program::compile(&parse(expression, &["x"]), &[], context)
+			.is_some();
+		assert!(!compile("mean [x, 1]"));
+		assert!(!compile("sum(k, 1, 3, k * x)"));
+		context.push_value(Value::List(vec![1.0]));
+		let compile = |expression| Program::compile(&parse(expression, &["x"]), &[], context)
+			.is_some();
+		assert!(!compile("$ + 1"));
+	}
+
+	/// Compares the time taken to evaluate an expression repeatedly by
+	/// the tree walk and by a program. Run with `--release --ignored`.
+	#[test]
+	#[ignore]
+	fn bench_program() {
+		let context = &mut Context::default();
+		context.push_value(Value::Number(2.0));
+		let expression = "if x > 0 then sin x ^ 2 + $ * x / (1 + x) else sqrt abs x - ln (2 + x ^ 2)";
+		let node = parse(expression, &["x"]);
+		let program = Program::compile(&node, &["x".to_owned()], context).unwrap();
+		let points = (0..1_000_000).map(|index| index as f64 / 1e5 - 5.0);
+
+		let start = std::time::Instant::now();
+		let tree: f64 = points.clone().map(|point| context.bind("x", point,
+			|| node.number(context)).unwrap()).sum();
+		let tree_time = start.elapsed();
+
+		let start = std::time::Instant::now();
+		let compiled: f64 = points.map(|point| program.run(context, &[point]).unwrap()).sum();
+		let program_time = start.elapsed();
+
+		println!("tree walk: {:?}, program: {:?}", tree_time, program_time);
+		assert_eq!(tree, compiled);
+		assert!(program_time * 2 < tree_time);
+	}
+}
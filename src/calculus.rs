@@ -0,0 +1,113 @@
+/// The absolute error targeted by adaptive quadrature.
+const TOLERANCE: f64 = 1e-10;
+
+/// The deepest subdivision of an interval during adaptive quadrature.
+const MAXIMUM_DEPTH: usize = 18;
+
+/// The number of step sizes used to extrapolate a derivative.
+const STEPS: usize = 10;
+
+/// The ratio between successive step sizes of a derivative.
+const SHRINK: f64 = 1.4;
+
+/// Integrates a function between two bounds with adaptive Simpson
+/// quadrature. Returns the integral with an estimate of its error.
+pub fn integrate<E>(function: &mut impl FnMut(f64) -> Result<f64, E>,
+                    lower: f64, upper: f64) -> Result<(f64, f64), E> {
+	let middle = (lower + upper) / 2.0;
+	let (lower, middle, upper) = ((lower, function(lower)?),
+		(middle, function(middle)?), (upper, function(upper)?));
+	let whole = simpson(lower, middle, upper);
+	adaptive(function, lower, middle, upper, whole, TOLERANCE, MAXIMUM_DEPTH)
+}
+
+fn adaptive<E>(function: &mut impl FnMut(f64) -> Result<f64, E>,
+               lower: (f64, f64), middle: (f64, f64), upper: (f64, f64),
+               whole: f64, tolerance: f64, depth: usize) -> Result<(f64, f64), E> {
+	let left_middle = (lower.0 + middle.0) / 2.0;
+	let right_middle = (middle.0 + upper.0) / 2.0;
+	let left_middle = (left_middle, function(left_middle)?);
+	let right_middle = (right_middle, function(right_middle)?);
+
+	let left = simpson(lower, left_middle, middle);
+	let right = simpson(middle, right_middle, upper);
+	let difference = left + right - whole;
+	if depth == 0 || difference.abs() <= 15.0 * tolerance {
+		return Ok((left + right + difference / 15.0, difference.abs() / 15.0));
+	}
+
+	let (left, left_error) = adaptive(function, lower, left_middle,
+		middle, left, tolerance / 2.0, depth - 1)?;
+	let (right, right_error) = adaptive(function, middle, right_middle,
+		upper, right, tolerance / 2.0, depth - 1)?;
+	Ok((left + right, left_error + right_error))
+}
+
+fn simpson((lower, f_lower): (f64, f64), (_, f_middle): (f64, f64),
+           (upper, f_upper): (f64, f64)) -> f64 {
+	(upper - lower) / 6.0 * (f_lower + 4.0 * f_middle + f_upper)
+}
+
+/// Differentiates a function at a point by extrapolating central
+/// differences of decreasing step sizes with Ridders' method.
+/// Returns the derivative with an estimate of its error.
+pub fn differentiate<E>(function: &mut impl FnMut(f64) -> Result<f64, E>,
+                        point: f64) -> Result<(f64, f64), E> {
+	let mut step = 0.1 * point.abs().max(1.0);
+	let mut central = |step: f64| -> Result<f64, E> {
+		Ok((function(point + step)? - function(point - step)?) / (2.0 * step))
+	};
+
+	let mut table = [[0.0; STEPS]; STEPS];
+	table[0][0] = central(step)?;
+	let (mut derivative, mut error) = (table[0][0], f64::INFINITY);
+	for column in 1..STEPS {
+		step /= SHRINK;
+		table[0][column] = central(step)?;
+
+		let mut factor = SHRINK * SHRINK;
+		for row in 1..=column {
+			table[row][column] = (table[row - 1][column] * factor - table[row - 1][column - 1])
+				/ (factor - 1.0);
+			factor *= SHRINK * SHRINK;
+
+			let estimate = (table[row][column] - table[row - 1][column]).abs()
+				.max((table[row][column] - table[row - 1][column - 1]).abs());
+			if estimate <= error {
+				error = estimate;
+				derivative = table[row][column];
+			}
+		}
+
+		let divergence = (table[column][column] - table[column - 1][column - 1]).abs();
+		if divergence >= 2.0 * error {
+			break;
+		}
+	}
+	Ok((derivative, error))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn exact(value: f64) -> Result<f64, ()> {
+		Ok(value)
+	}
+
+	#[test]
+	fn test_integrate() {
+		let (integral, error) = integrate(&mut |x| exact(x * x), 0.0, 3.0).unwrap();
+		assert!((integral - 9.0).abs() < 1e-12 && error < 1e-10);
+		let (integral, _) = integrate(&mut |x| exact(x.sin()), 0.0, std::f64::consts::PI).unwrap();
+		assert!((integral - 2.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_differentiate() {
+		let (derivative, error) = differentiate(&mut |x| exact(x.powi(3)), 2.0).unwrap();
+		assert!((derivative - 12.0).abs() < 1e-9 && error < 1e-6);
+		let (derivative, _) = differentiate(&mut |x| exact(x.exp()), 1.0).unwrap();
+		assert!((derivative - std::f64::consts::E).abs() < 1e-9);
+	}
+}
@@ -5,38 +5,139 @@ use crate::lexer::Lexer;
 use crate::span::{Span, Spanned};
 use crate::token::{Operator, Token};
 
+/// Tracks the functions that are waiting for arguments.
+#[derive(Debug, Default)]
+struct Arguments {
+	/// The index of each pending function and its remaining argument count.
+	pending: Vec<(usize, usize)>,
+	/// The index of the function applied by the last term if the
+	/// function can take an additional argument.
+	extensible: Option<usize>,
+}
+
+/// The bracket that encloses a coalescence.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Delimiter {
+	Root,
+	Parenthesis,
+	Bracket,
+	Arguments,
+	/// The condition of a conditional that ends with `then`.
+	Condition,
+	/// The consequent of a conditional that ends with `else`.
+	Consequent,
+}
+
+impl Delimiter {
+	/// Whether the token ends an element of a comma separated
+	/// sequence or a part of a conditional.
+	fn separator(&self, token: &Token) -> bool {
+		match self {
+			Delimiter::Bracket => matches!(token, Token::BracketClose | Token::Comma),
+			Delimiter::Arguments => matches!(token, Token::ParenthesisClose | Token::Comma),
+			Delimiter::Condition => matches!(token, Token::Then),
+			Delimiter::Consequent => matches!(token, Token::Else),
+			_ => false,
+		}
+	}
+}
+
 pub fn coalesce_root(lexer: &mut Lexer) -> Result<Coalescence, Spanned<Error>> {
-	coalesce(lexer, false, false)
+	coalesce_bound(lexer, &[])
 }
 
-fn coalesce(lexer: &mut Lexer, mut last_valued: bool, expect_parenthesis: bool)
-            -> Result<Coalescence, Spanned<Error>> {
+/// Coalesces an expression in which the identifiers are bound.
+pub fn coalesce_bound(lexer: &mut Lexer, bound: &[String]) -> Result<Coalescence, Spanned<Error>> {
+	coalesce(lexer, false, Delimiter::Root, bound).map(|(coalescence, _)| coalescence)
+}
+
+/// Coalesces tokens until the closing token of the delimiter and
+/// returns the coalescence with the closing token if there is one.
+/// Identifiers are valid only if they are bound by an enclosing function.
+fn coalesce(lexer: &mut Lexer, mut last_valued: bool, delimiter: Delimiter, bound: &[String])
+            -> Result<(Coalescence, Option<Spanned<Token>>), Spanned<Error>> {
+	let mut closing = None;
 	let mut last_byte_end = 0;
 	let mut coalesces = Vec::new();
+	let arguments = &mut Arguments::default();
 	while let Some(token) = lexer.next() {
 		let token = token?;
 		let span = token.span;
 		last_byte_end = token.span.byte_end();
+		let extensible = arguments.extensible.take();
 		match token.node {
-			Token::ParenthesisClose if expect_parenthesis => match coalesces.is_empty() {
-				true => return Err(token.map(Error::EmptyBrackets)),
-				false => break,
-			},
-			Token::ParenthesisClose => return Err(token.map(Error::MismatchedBracket)),
+			Token::ParenthesisClose if delimiter == Delimiter::Parenthesis =>
+				match coalesces.is_empty() {
+					true => return Err(token.map(Error::EmptyBrackets)),
+					false => break,
+				},
+			ref separator if delimiter.separator(separator) =>
+				match (coalesces.is_empty(), &token.node) {
+					(true, Token::Comma | Token::Then | Token::Else) =>
+						return Err(token.map(Error::ExpectedValued)),
+					(true, _) => return Err(token.map(Error::EmptyBrackets)),
+					(false, _) => {
+						closing = Some(token);
+						break;
+					}
+				},
+			Token::ParenthesisClose | Token::BracketClose =>
+				return Err(token.map(Error::MismatchedBracket)),
+			Token::Comma => return Err(token.map(Error::MisplacedComma)),
+			Token::Then | Token::Else => return Err(token.map(Error::MisplacedKeyword)),
+			Token::If => {
+				let condition = keyword(lexer, Delimiter::Condition, "then", bound)?;
+				let consequent = keyword(lexer, Delimiter::Consequent, "else", bound)?;
+				let (alternative, alternative_closing) = coalesce(lexer, false, delimiter, bound)?;
+				let span = Span(span.byte_start(), alternative.byte_end());
+				let conditional = Coalescence::Conditional(Box::new(condition),
+					Box::new(consequent), Box::new(alternative), span);
+				value(&mut coalesces, &mut last_valued, arguments, extensible, conditional, span)?;
+
+				last_byte_end = span.byte_end();
+				closing = alternative_closing;
+				break;
+			}
 			Token::ParenthesisOpen => {
 				if last_valued {
-					let operator = Spanned::new(Operator::Multiply, span);
-					coalesces.push(Coalescence::Operator(operator));
+					match extensible {
+						Some(index) => extend(&mut coalesces, arguments, index),
+						None => {
+							let operator = Spanned::new(Operator::Multiply, span);
+							coalesces.push(Coalescence::Operator(operator));
+						}
+					}
 				}
 
-				coalesces.push(coalesce(lexer, false, true)?);
-				last_valued = true;
+				coalesces.push(coalesce(lexer, false, Delimiter::Parenthesis, bound)?.0);
+				last_valued = complete(&coalesces, arguments);
+			}
+			Token::BracketOpen => {
+				let (elements, span) = elements(lexer, span, Delimiter::Bracket, bound)?;
+				let list = Coalescence::List(elements, span);
+				value(&mut coalesces, &mut last_valued, arguments, extensible, list, span)?
+			}
+			Token::Range => {
+				let start = match coalesces.pop() {
+					Some(Coalescence::Variable(variable)) => variable,
+					_ => return Err(token.map(Error::ExpectedVariable)),
+				};
+
+				let end = match lexer.next().transpose()? {
+					Some(Spanned { node: Token::Variable(variable), span }) =>
+						Spanned::new(variable, span),
+					Some(token) => return Err(token.map(Error::ExpectedVariable)),
+					None => return Err(Spanned::new(Error::ExpectedVariable,
+						Span(span.byte_end(), span.byte_end() + 1))),
+				};
+				coalesces.push(Coalescence::Range(start, end));
 			}
 			Token::Operator(operator) => {
 				if !last_valued {
 					match operator {
 						Operator::Minus => {
 							let function = Spanned::new(Function::UnaryMinus, span);
+							arguments.pending.push((coalesces.len(), 1));
 							coalesces.push(Coalescence::Function(function));
 							continue;
 						}
@@ -47,51 +148,158 @@ fn coalesce(lexer: &mut Lexer, mut last_valued: bool, expect_parenthesis: bool)
 				coalesces.push(Coalescence::Operator(token.map(operator)));
 				last_valued = false;
 			}
-			Token::Terminal(terminal) => value(&mut coalesces, &mut last_valued,
-				Coalescence::Terminal(token.map(terminal)), span)?,
-			Token::Variable(variable) => value(&mut coalesces, &mut last_valued,
-				Coalescence::Variable(Spanned::new(variable, span)), span)?,
-			Token::Function(function) => match last_valued {
-				false => coalesces.push(Coalescence::Function(Spanned::new(function, span))),
-				true => return Err(Spanned::new(Error::ExpectedOperator, span)),
+			Token::Terminal(terminal) => value(&mut coalesces, &mut last_valued, arguments,
+				extensible, Coalescence::Terminal(token.map(terminal)), span)?,
+			Token::Variable(variable) => value(&mut coalesces, &mut last_valued, arguments,
+				extensible, Coalescence::Variable(Spanned::new(variable, span)), span)?,
+			Token::Identifier(identifier) => match bound.contains(&identifier) {
+				true => value(&mut coalesces, &mut last_valued, arguments, extensible,
+					Coalescence::Identifier(Spanned::new(identifier, span)), span)?,
+				false => return Err(Spanned::new(Error::InvalidItem, span)),
 			},
-			Token::Constant(constant) => value(&mut coalesces, &mut last_valued,
-				Coalescence::Terminal(Spanned::new(constant.value(), span)), span)?,
-			Token::Coalesce(mut count) => {
-				count += 1;
-				let mut iterator = coalesces.iter().enumerate().rev();
-				while let Some((index, coalesce)) = iterator.next() {
-					match coalesce {
-						Coalescence::Operator(_) => continue,
-						_ => count -= 1,
+			Token::Function(function) => match (last_valued, binding(lexer, &function, bound)) {
+				(false, Some((function, variable, explicit))) => {
+					let open = lexer.next().unwrap()?;
+					if explicit {
+						lexer.nth(1);
 					}
 
-					if count == 0 {
-						let coalescence = coalesces.split_off(index);
-						coalesces.push(Coalescence::Multiple(coalescence));
-						break;
+					let bound = &[bound, &[variable]].concat();
+					let (elements, elements_span) =
+						elements(lexer, open.span, Delimiter::Arguments, bound)?;
+					if elements.len() != function.arity() {
+						let error = Error::ExpectedArguments(function.arity() + explicit as usize);
+						return Err(Spanned::new(error, elements_span));
 					}
+
+					let span = Span(span.byte_start(), elements_span.byte_end());
+					let call = Coalescence::Call(Spanned::new(function, token.span), elements, span);
+					value(&mut coalesces, &mut last_valued, arguments, extensible, call, span)?
+				}
+				(false, None) => {
+					arguments.pending.push((coalesces.len(), function.arity()));
+					coalesces.push(Coalescence::Function(Spanned::new(function, span)));
+				}
+				(true, _) => return Err(Spanned::new(Error::ExpectedOperator, span)),
+			},
+			Token::Constant(constant) => value(&mut coalesces, &mut last_valued, arguments,
+				extensible, Coalescence::Terminal(Spanned::new(constant.value(), span)), span)?,
+			Token::Factorial => match coalesces.pop() {
+				Some(term) if term.is_term() => {
+					let function = Spanned::new(Function::Factorial, span);
+					coalesces.push(Coalescence::Postfix(Box::new(term), function));
 				}
+				_ => return Err(token.map(Error::ExpectedValued)),
+			},
+			Token::Coalesce(count) => {
+				let starts = starts(&coalesces);
+				let index = match starts.len().checked_sub(count + 1) {
+					Some(index) => starts[index],
+					None => return Err(token.map(Error::InvalidCoalesce)),
+				};
 
-				if count > 0 {
-					return Err(token.map(Error::InvalidCoalesce));
+				let coalescence = coalesces.split_off(index);
+				match state(&coalescence) {
+					Some((true, _)) => (),
+					_ => return Err(token.map(Error::InvalidCoalesce)),
 				}
+
+				coalesces.push(Coalescence::Multiple(coalescence));
+				let (valued, pending) = state(&coalesces)
+					.ok_or_else(|| token.map(Error::InvalidCoalesce))?;
+				last_valued = valued;
+				arguments.pending = pending;
 			}
 		}
 	}
 
 	let last_span = Span(last_byte_end, last_byte_end + 1);
 	match last_valued {
-		true => Ok(Coalescence::Multiple(coalesces)),
+		true => Ok((Coalescence::Multiple(coalesces), closing)),
 		false => Err(Spanned::new(Error::ExpectedValued, last_span))
 	}
 }
 
-fn value(coalesces: &mut Vec<Coalescence>, last_valued: &mut bool,
-         value: Coalescence, span: Span) -> Result<(), Spanned<Error>> {
+/// Coalesces the part of a conditional that ends with a keyword.
+/// The alternative of a conditional extends to the end of the
+/// enclosing delimiter and so is not coalesced by this function.
+fn keyword(lexer: &mut Lexer, delimiter: Delimiter, keyword: &'static str, bound: &[String])
+           -> Result<Coalescence, Spanned<Error>> {
+	match coalesce(lexer, false, delimiter, bound)? {
+		(coalescence, Some(_)) => Ok(coalescence),
+		(coalescence, None) => {
+			let byte_end = coalescence.byte_end();
+			Err(Spanned::new(Error::ExpectedKeyword(keyword), Span(byte_end, byte_end + 1)))
+		}
+	}
+}
+
+/// Coalesces comma separated elements after the token with the given
+/// span. Returns the elements with the span from the token to the end.
+fn elements(lexer: &mut Lexer, span: Span, delimiter: Delimiter, bound: &[String])
+            -> Result<(Vec<Coalescence>, Span), Spanned<Error>> {
+	let mut elements = Vec::new();
+	loop {
+		let (element, closing) = coalesce(lexer, false, delimiter, bound)?;
+		let byte_end = element.byte_end();
+		elements.push(element);
+		match closing {
+			Some(Spanned { node: Token::Comma, .. }) => continue,
+			Some(closing) => return Ok((elements,
+				Span(span.byte_start(), closing.span.byte_end()))),
+			None => return Ok((elements, Span(span.byte_start(), byte_end))),
+		}
+	}
+}
+
+/// Finds the form of a function that binds a variable if the function
+/// is followed by parenthesised arguments. The variable is explicit if
+/// the arguments start with the variable such as `sum(k, ...` and is
+/// otherwise the first free identifier of the first argument such as
+/// `integrate(x^2, ...`. Returns the form with the bound variable and
+/// whether the variable is explicit.
+fn binding(lexer: &Lexer, function: &Function, bound: &[String])
+           -> Option<(Function, String, bool)> {
+	let mut lexer = lexer.clone();
+	if lexer.next()?.ok()?.node != Token::ParenthesisOpen {
+		return None;
+	}
+
+	if let Function::Calculus(_, _) = function {
+		let variable = free(lexer, bound).unwrap_or_default();
+		return Some((function.binding(variable.clone())?, variable, false));
+	}
+
+	match (lexer.next()?.ok()?.node, lexer.next()?.ok()?.node) {
+		(Token::Identifier(variable), Token::Comma) =>
+			Some((function.binding(variable.clone())?, variable, true)),
+		_ => None,
+	}
+}
+
+/// Finds the first identifier of an argument that is not already bound.
+fn free(lexer: Lexer, bound: &[String]) -> Option<String> {
+	let mut depth = 0;
+	for token in lexer {
+		match token.ok()?.node {
+			Token::ParenthesisOpen | Token::BracketOpen => depth += 1,
+			Token::ParenthesisClose | Token::BracketClose | Token::Comma if depth == 0 =>
+				return None,
+			Token::ParenthesisClose | Token::BracketClose => depth -= 1,
+			Token::Identifier(identifier) if !bound.contains(&identifier) =>
+				return Some(identifier),
+			_ => (),
+		}
+	}
+	None
+}
+
+fn value(coalesces: &mut Vec<Coalescence>, last_valued: &mut bool, arguments: &mut Arguments,
+         extensible: Option<usize>, value: Coalescence, span: Span) -> Result<(), Spanned<Error>> {
 	if *last_valued {
-		match coalesces.last() {
-			Some(Coalescence::Multiple(_)) => {
+		match (extensible, coalesces.last()) {
+			(Some(index), _) => extend(coalesces, arguments, index),
+			(None, Some(Coalescence::Multiple(_))) | (None, Some(Coalescence::Postfix(_, _))) => {
 				let byte_start = value.byte_start();
 				let span = Span(byte_start, byte_start + 1);
 				let operator = Spanned::new(Operator::Multiply, span);
@@ -101,6 +309,84 @@ fn value(coalesces: &mut Vec<Coalescence>, last_valued: &mut bool,
 		}
 	}
 
-	*last_valued = true;
-	Ok(coalesces.push(value))
+	coalesces.push(value);
+	*last_valued = complete(coalesces, arguments);
+	Ok(())
+}
+
+/// Replaces a function with its form that takes an additional
+/// argument and waits for the additional argument.
+fn extend(coalesces: &mut [Coalescence], arguments: &mut Arguments, index: usize) {
+	if let Coalescence::Function(function) = &mut coalesces[index] {
+		function.node = function.node.extend().unwrap();
+		arguments.pending.push((index, 1));
+	}
+}
+
+/// Accounts for a term that supplies an argument to the innermost
+/// pending function. Returns whether every function has all of
+/// its arguments and the expression can be continued by an operator.
+fn complete(coalesces: &[Coalescence], arguments: &mut Arguments) -> bool {
+	let mut completed = None;
+	while let Some((index, count)) = arguments.pending.last_mut() {
+		*count -= 1;
+		match *count {
+			0 => completed = Some(*index),
+			_ => return false,
+		}
+		arguments.pending.pop();
+	}
+
+	arguments.extensible = completed.filter(|index| match &coalesces[*index] {
+		Coalescence::Function(function) => function.node.extend().is_some(),
+		_ => false,
+	});
+	true
+}
+
+/// Finds the index of the start of each term that a coalesce can combine.
+/// Every operand and every function is the start of a term except for
+/// those in an argument of a function that is followed by another
+/// argument. A function with every argument except its last is
+/// therefore a single term so that a coalesce never separates
+/// the arguments of a function.
+pub fn starts(coalesces: &[Coalescence]) -> Vec<usize> {
+	let mut starts = Vec::new();
+	let mut arguments = Arguments::default();
+	for (index, coalesce) in coalesces.iter().enumerate() {
+		let leading = arguments.pending.iter().any(|(_, count)| *count > 1);
+		match coalesce {
+			Coalescence::Operator(_) => continue,
+			_ if leading => (),
+			_ => starts.push(index),
+		}
+
+		match coalesce {
+			Coalescence::Function(function) =>
+				arguments.pending.push((index, function.node.arity())),
+			_ => {
+				complete(coalesces, &mut arguments);
+			}
+		}
+	}
+	starts
+}
+
+/// Determines whether a sequence of coalesces ends with a complete term
+/// and the functions that remain pending. Returns `None` if the
+/// sequence is not a valid expression prefix.
+fn state(coalesces: &[Coalescence]) -> Option<(bool, Vec<(usize, usize)>)> {
+	let mut valued = false;
+	let mut arguments = Arguments::default();
+	for (index, coalesce) in coalesces.iter().enumerate() {
+		match coalesce {
+			Coalescence::Operator(_) if valued => valued = false,
+			Coalescence::Function(function) if !valued =>
+				arguments.pending.push((index, function.node.arity())),
+			Coalescence::Operator(_) | Coalescence::Function(_) => return None,
+			_ if valued => return None,
+			_ => valued = complete(coalesces, &mut arguments),
+		}
+	}
+	Some((valued, arguments.pending))
 }
@@ -1,7 +1,7 @@
 use crate::context::Context;
 use crate::error::Error;
 use crate::item::Function;
-use crate::span::Spanned;
+use crate::span::{Span, Spanned};
 use crate::token::Operator;
 
 #[derive(Debug)]
@@ -11,6 +11,13 @@ pub enum Coalescence {
 	Terminal(Spanned<f64>),
 	Variable(Spanned<String>),
 	Function(Spanned<Function>),
+	Postfix(Box<Coalescence>, Spanned<Function>),
+	List(Vec<Coalescence>, Span),
+	Range(Spanned<String>, Spanned<String>),
+	Identifier(Spanned<String>),
+	Call(Spanned<Function>, Vec<Coalescence>, Span),
+	/// A condition, the consequent and the alternative.
+	Conditional(Box<Coalescence>, Box<Coalescence>, Box<Coalescence>, Span),
 }
 
 impl Coalescence {
@@ -20,30 +27,82 @@ impl Coalescence {
 				.try_for_each(|coalesce| coalesce.verify(context)),
 			Coalescence::Variable(variable) => context.variable(&variable.node)
 				.map_err(|error| Spanned::new(error, variable.span)).map(|_| ()),
+			Coalescence::Postfix(coalescence, _) => coalescence.verify(context),
+			Coalescence::List(elements, _) | Coalescence::Call(_, elements, _) => elements.iter()
+				.try_for_each(|element| element.verify(context)),
+			Coalescence::Conditional(condition, consequent, alternative, _) =>
+				[condition, consequent, alternative].iter()
+					.try_for_each(|coalescence| coalescence.verify(context)),
+			Coalescence::Range(start, end) => [start, end].iter().try_for_each(|variable|
+				context.index(&variable.node).map_err(|error| Spanned::new(error, variable.span))
+					.map(|_| ())),
 			_ => Ok(()),
 		}
 	}
 
+	/// Whether the coalescence is a complete term that
+	/// can be an operand or a function argument.
+	pub fn is_term(&self) -> bool {
+		!matches!(self, Coalescence::Operator(_) | Coalescence::Function(_))
+	}
+
 	pub fn coalesce_anchors(&self) -> Vec<usize> {
 		match self {
-			Coalescence::Multiple(coalesces) => {
-				let mut anchors = Vec::new();
-				let mut coalesces = coalesces.iter();
-				while let Some(coalesce) = coalesces.next() {
-					match coalesce {
-						Coalescence::Operator(_) => continue,
-						_ => anchors.push(coalesce.byte_start()),
-					}
-				}
-				anchors
-			}
+			Coalescence::Multiple(coalesces) => crate::coalesce::starts(coalesces).into_iter()
+				.map(|index| coalesces[index].byte_start()).collect(),
 			Coalescence::Terminal(_) => vec![self.byte_start()],
 			Coalescence::Variable(_) => vec![self.byte_start()],
 			Coalescence::Function(_) => vec![self.byte_start()],
+			Coalescence::Postfix(_, _) => vec![self.byte_start()],
+			Coalescence::List(_, _) => vec![self.byte_start()],
+			Coalescence::Range(_, _) => vec![self.byte_start()],
+			Coalescence::Identifier(_) => vec![self.byte_start()],
+			Coalescence::Call(_, _, _) => vec![self.byte_start()],
+			Coalescence::Conditional(_, _, _, _) => vec![self.byte_start()],
 			Coalescence::Operator(_) => vec![],
 		}
 	}
 
+	/// Finds the groups of terms that are combined by a coalesce or by
+	/// parentheses. The groups are arranged by their depth of nesting
+	/// such that the outermost groups are first.
+	pub fn coalesce_groups(&self) -> Vec<Vec<Span>> {
+		let mut groups = Vec::new();
+		self.nested_groups(0, &mut groups);
+		groups
+	}
+
+	/// Finds the groups of the coalescence that includes the
+	/// coalescence itself if it is a group of multiple terms.
+	fn groups(&self, depth: usize, groups: &mut Vec<Vec<Span>>) {
+		match self {
+			Coalescence::Multiple(coalesces) if coalesces.len() > 1 => {
+				if groups.len() == depth {
+					groups.push(Vec::new());
+				}
+
+				groups[depth].push(Span(self.byte_start(), self.byte_end()));
+				coalesces.iter().for_each(|coalesce| coalesce.groups(depth + 1, groups));
+			}
+			_ => self.nested_groups(depth, groups),
+		}
+	}
+
+	/// Finds the groups that are nested inside the coalescence.
+	fn nested_groups(&self, depth: usize, groups: &mut Vec<Vec<Span>>) {
+		match self {
+			Coalescence::Multiple(coalesces) => coalesces.iter()
+				.for_each(|coalesce| coalesce.groups(depth, groups)),
+			Coalescence::Postfix(coalescence, _) => coalescence.groups(depth, groups),
+			Coalescence::List(elements, _) | Coalescence::Call(_, elements, _) => elements.iter()
+				.for_each(|element| element.nested_groups(depth, groups)),
+			Coalescence::Conditional(condition, consequent, alternative, _) =>
+				[condition, consequent, alternative].iter()
+					.for_each(|coalescence| coalescence.nested_groups(depth, groups)),
+			_ => (),
+		}
+	}
+
 	pub fn byte_start(&self) -> usize {
 		match self {
 			Coalescence::Multiple(coalesces) => coalesces.first().unwrap().byte_start(),
@@ -51,6 +110,12 @@ impl Coalescence {
 			Coalescence::Terminal(terminal) => terminal.span.byte_start(),
 			Coalescence::Variable(variable) => variable.span.byte_start(),
 			Coalescence::Function(function) => function.span.byte_start(),
+			Coalescence::Postfix(coalescence, _) => coalescence.byte_start(),
+			Coalescence::List(_, span) => span.byte_start(),
+			Coalescence::Range(start, _) => start.span.byte_start(),
+			Coalescence::Identifier(identifier) => identifier.span.byte_start(),
+			Coalescence::Call(_, _, span) => span.byte_start(),
+			Coalescence::Conditional(_, _, _, span) => span.byte_start(),
 		}
 	}
 
@@ -61,6 +126,31 @@ impl Coalescence {
 			Coalescence::Terminal(terminal) => terminal.span.byte_end(),
 			Coalescence::Variable(variable) => variable.span.byte_end(),
 			Coalescence::Function(function) => function.span.byte_end(),
+			Coalescence::Postfix(_, function) => function.span.byte_end(),
+			Coalescence::List(_, span) => span.byte_end(),
+			Coalescence::Range(_, end) => end.span.byte_end(),
+			Coalescence::Identifier(identifier) => identifier.span.byte_end(),
+			Coalescence::Call(_, _, span) => span.byte_end(),
+			Coalescence::Conditional(_, _, _, span) => span.byte_end(),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::lexer::Lexer;
+	use crate::span::Span;
+
+	fn groups(expression: &str) -> Vec<Vec<Span>> {
+		let lexer = &mut Lexer::new(expression);
+		crate::coalesce::coalesce_root(lexer).unwrap().coalesce_groups()
+	}
+
+	#[test]
+	fn test_coalesce_groups() {
+		assert_eq!(groups("1 / 2 - 3 + 4; ;"), vec![vec![Span(4, 13)], vec![Span(8, 13)]]);
+		assert_eq!(groups("1 + 2; * (3 + 4)!"), vec![vec![Span(0, 5), Span(10, 15)]]);
+		assert_eq!(groups("[1 + 2;, 3] * (4)"), vec![vec![Span(1, 6)]]);
+		assert!(groups("1 + 2").is_empty());
+	}
+}
@@ -0,0 +1,413 @@
+use crate::error::Error;
+use crate::item::AngleUnit;
+use crate::lexer::Lexer;
+use crate::node::Node;
+use crate::numeric::Rounding;
+use crate::span::{Span, Spanned};
+use crate::token::Token;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+	Mode(AngleUnit),
+	Rounding(Rounding),
+	Factor(Spanned<Node>),
+	Solve(Box<Solve>),
+	/// Shows the grouping of an expression as a parenthesised
+	/// expression or as a tree if the flag is set.
+	Explain(Spanned<Node>, bool),
+	/// Rewrites an expression with coalesces in place of parentheses.
+	Coalesce(Spanned<Node>),
+	/// Evaluates the constant terms of an expression and
+	/// removes the operations that leave a number unchanged.
+	Simplify(Spanned<Node>),
+	Differentiate(Box<Differentiate>),
+	Plot(Box<Plot>),
+	Table(Box<Table>),
+}
+
+/// Expressions of `x` to plot over an interval such
+/// as `plot sin x, cos x from -pi to pi`.
+#[derive(Debug, PartialEq)]
+pub struct Plot {
+	pub expressions: Vec<Spanned<Node>>,
+	pub lower: Spanned<Node>,
+	pub upper: Spanned<Node>,
+}
+
+/// A derivative with respect to a variable such as `d/dx x^2`
+/// that is evaluated if it is followed by `at point`.
+#[derive(Debug, PartialEq)]
+pub struct Differentiate {
+	pub expression: Spanned<Node>,
+	pub variable: String,
+	pub point: Option<Spanned<Node>>,
+}
+
+/// The values of an expression over a range of a variable such
+/// as `table x^2 for x in 0..10 step 0.5` that are written as
+/// comma separated values if the flag is set.
+#[derive(Debug, PartialEq)]
+pub struct Table {
+	pub expression: Spanned<Node>,
+	pub variable: String,
+	pub start: Spanned<Node>,
+	pub end: Spanned<Node>,
+	pub step: Option<Spanned<Node>>,
+	pub csv: bool,
+}
+
+/// An equation to solve for a variable such as `x^2 = 2 for x`.
+#[derive(Debug, PartialEq)]
+pub struct Solve {
+	pub left: Spanned<Node>,
+	pub right: Option<Spanned<Node>>,
+	pub variable: String,
+	pub search: Option<Search>,
+	pub span: Span,
+}
+
+/// Where to search for the roots of an equation.
+#[derive(Debug, PartialEq)]
+pub enum Search {
+	Interval(Spanned<Node>, Spanned<Node>),
+	Guess(Spanned<Node>),
+}
+
+/// Parses a command from the leading word of an expression.
+/// Returns `None` if the expression is not a command.
+pub fn command(expression: &str) -> Option<Result<Command, Spanned<Error>>> {
+	let byte_start = expression.len() - expression.trim_start().len();
+	let keyword_end = expression[byte_start..].find(char::is_whitespace)
+		.map(|index| byte_start + index).unwrap_or(expression.len());
+	let argument = argument(expression, keyword_end);
+
+	Some(match &expression[byte_start..keyword_end] {
+		"mode" => AngleUnit::parse(argument.node).map(Command::Mode)
+			.ok_or_else(|| argument.map(Error::InvalidAngleUnit)),
+		"rounding" => Rounding::parse(argument.node).map(Command::Rounding)
+			.ok_or_else(|| argument.map(Error::InvalidRounding)),
+		"factor" => node(expression, argument, &[]).map(Command::Factor),
+		"solve" => solve(expression, argument).map(|solve| Command::Solve(Box::new(solve))),
+		"explain" => match word(argument, "tree") {
+			Some(argument) => node(expression, argument, &[]).map(|node| Command::Explain(node, true)),
+			None => node(expression, argument, &[]).map(|node| Command::Explain(node, false)),
+		},
+		"coalesce" => node(expression, argument, &[]).map(Command::Coalesce),
+		"simplify" => node(expression, argument, &[]).map(Command::Simplify),
+		"plot" => plot(expression, argument).map(|plot| Command::Plot(Box::new(plot))),
+		"table" => match word(argument, "csv") {
+			Some(argument) => table(expression, argument, true),
+			None => table(expression, argument, false),
+		}.map(|table| Command::Table(Box::new(table))),
+		keyword if keyword.starts_with("d/d") => {
+			let variable = Spanned::new(&keyword[3..], Span(byte_start + 3, keyword_end.max(byte_start + 4)));
+			differentiate(expression, variable, argument)
+				.map(|differentiate| Command::Differentiate(Box::new(differentiate)))
+		}
+		_ => return None,
+	})
+}
+
+/// Parses an equation of the form `left = right for variable` that is
+/// optionally followed by `from lower to upper` or `near guess`.
+/// The right side of the equation is zero if it is omitted.
+fn solve(expression: &str, argument: Spanned<&str>) -> Result<Solve, Spanned<Error>> {
+	let (equation, rest) = split(argument, "for")
+		.ok_or_else(|| end(argument).map(Error::ExpectedKeyword("for")))?;
+	let (variable, search) = match (split(rest, "from"), split(rest, "near")) {
+		(Some((variable, interval)), _) => {
+			let (lower, upper) = split(interval, "to")
+				.ok_or_else(|| end(interval).map(Error::ExpectedKeyword("to")))?;
+			(variable, Some((lower, Some(upper))))
+		}
+		(None, Some((variable, guess))) => (variable, Some((guess, None))),
+		(None, None) => (rest, None),
+	};
+
+	let variable = self::variable(variable)?;
+	let bound = std::slice::from_ref(&variable);
+	let (left, right) = match equals(equation.node) {
+		Some(index) => (part(equation, 0, index),
+			Some(part(equation, index + 1, equation.node.len()))),
+		None => (equation, None),
+	};
+
+	let left = node(expression, left, bound)?;
+	let right = right.map(|right| node(expression, right, bound)).transpose()?;
+	let search = match search {
+		None => None,
+		Some((guess, None)) => Some(Search::Guess(node(expression, guess, &[])?)),
+		Some((lower, Some(upper))) => Some(Search::Interval(node(expression, lower, &[])?,
+			node(expression, upper, &[])?)),
+	};
+
+	let byte_end = right.as_ref().unwrap_or(&left).span.byte_end();
+	let span = Span(left.span.byte_start(), byte_end);
+	Ok(Solve { left, right, variable, search, span })
+}
+
+/// Parses the expression of a derivative that is
+/// optionally followed by `at point`.
+fn differentiate(string: &str, variable: Spanned<&str>, argument: Spanned<&str>)
+                 -> Result<Differentiate, Spanned<Error>> {
+	let variable = self::variable(variable)?;
+	let (argument, point) = match split(argument, "at") {
+		Some((argument, point)) => (argument, Some(point)),
+		None => (argument, None),
+	};
+
+	let expression = node(string, argument, std::slice::from_ref(&variable))?;
+	let point = point.map(|point| node(string, point, &[])).transpose()?;
+	Ok(Differentiate { expression, variable, point })
+}
+
+/// Parses expressions separated by commas that
+/// are followed by `from lower to upper`.
+fn plot(string: &str, argument: Spanned<&str>) -> Result<Plot, Spanned<Error>> {
+	let (expressions, interval) = split(argument, "from")
+		.ok_or_else(|| end(argument).map(Error::ExpectedKeyword("from")))?;
+	let (lower, upper) = split(interval, "to")
+		.ok_or_else(|| end(interval).map(Error::ExpectedKeyword("to")))?;
+
+	let bound = &["x".to_owned()];
+	let expressions = commas(expressions).into_iter()
+		.map(|expression| node(string, expression, bound)).collect::<Result<_, _>>()?;
+	Ok(Plot { expressions, lower: node(string, lower, &[])?, upper: node(string, upper, &[])? })
+}
+
+/// Parses an expression of the form `expression for variable in
+/// start..end` that is optionally followed by `step step`.
+fn table(string: &str, argument: Spanned<&str>, csv: bool) -> Result<Table, Spanned<Error>> {
+	let (expression, rest) = split(argument, "for")
+		.ok_or_else(|| end(argument).map(Error::ExpectedKeyword("for")))?;
+	let (variable, range) = split(rest, "in")
+		.ok_or_else(|| end(rest).map(Error::ExpectedKeyword("in")))?;
+	let (range, step) = match split(range, "step") {
+		Some((range, step)) => (range, Some(step)),
+		None => (range, None),
+	};
+
+	let index = range.node.find("..")
+		.ok_or_else(|| end(range).map(Error::ExpectedKeyword("..")))?;
+	let variable = self::variable(variable)?;
+	Ok(Table {
+		expression: node(string, expression, std::slice::from_ref(&variable))?,
+		variable,
+		start: node(string, part(range, 0, index), &[])?,
+		end: node(string, part(range, index + 2, range.node.len()), &[])?,
+		step: step.map(|step| node(string, step, &[])).transpose()?,
+		csv,
+	})
+}
+
+/// Splits an argument at the commas that are not within brackets.
+fn commas(argument: Spanned<&str>) -> Vec<Spanned<&str>> {
+	let (mut parts, mut start, mut depth) = (Vec::new(), 0, 0);
+	for (index, byte) in argument.node.bytes().enumerate() {
+		match byte {
+			b'(' | b'[' => depth += 1,
+			b')' | b']' => depth -= 1,
+			b',' if depth == 0 => {
+				parts.push(part(argument, start, index));
+				start = index + 1;
+			}
+			_ => (),
+		}
+	}
+
+	parts.push(part(argument, start, argument.node.len()));
+	parts
+}
+
+/// Parses a variable that is a single identifier.
+fn variable(variable: Spanned<&str>) -> Result<String, Spanned<Error>> {
+	let mut tokens = Lexer::new(variable.node);
+	match (tokens.next(), tokens.next()) {
+		(Some(Ok(Spanned { node: Token::Identifier(identifier), .. })), None) => Ok(identifier),
+		_ => Err(variable.map(Error::ExpectedVariable)),
+	}
+}
+
+/// Finds the equals sign of an equation that is not part of a comparison.
+fn equals(string: &str) -> Option<usize> {
+	let bytes = string.as_bytes();
+	(0..bytes.len()).find(|index| bytes[*index] == b'=' && bytes.get(index + 1) != Some(&b'=') &&
+		!(*index > 0 && b"<>=!".contains(&bytes[index - 1])))
+}
+
+/// Parses a part of an expression with the identifiers that are bound.
+fn node(string: &str, argument: Spanned<&str>, bound: &[String])
+        -> Result<Spanned<Node>, Spanned<Error>> {
+	if argument.node.is_empty() {
+		return Err(argument.map(Error::ExpectedValued));
+	}
+
+	let byte_end = argument.span.byte_start() + argument.node.len();
+	let lexer = &mut Lexer::offset(&string[..byte_end], argument.span.byte_start());
+	let coalescence = crate::coalesce::coalesce_bound(lexer, bound)?;
+	Ok(crate::parse::parse_root(coalescence))
+}
+
+/// Splits an argument at the first occurrence of a keyword that is
+/// a separate word. Returns the parts before and after the keyword.
+fn split<'a>(argument: Spanned<&'a str>, keyword: &str)
+             -> Option<(Spanned<&'a str>, Spanned<&'a str>)> {
+	let string = argument.node;
+	let separate = |index: usize| string[..index].ends_with(char::is_whitespace) &&
+		string[index + keyword.len()..].chars().next().is_none_or(char::is_whitespace);
+	let (index, _) = string.match_indices(keyword).find(|(index, _)| separate(*index))?;
+	Some((part(argument, 0, index), part(argument, index + keyword.len(), string.len())))
+}
+
+/// Takes the remainder of an argument that starts with a separate word.
+fn word<'a>(argument: Spanned<&'a str>, word: &str) -> Option<Spanned<&'a str>> {
+	let remainder = argument.node.strip_prefix(word)?;
+	match remainder.starts_with(char::is_whitespace) {
+		true => Some(part(argument, word.len(), argument.node.len())),
+		false => None,
+	}
+}
+
+/// Takes the part of an argument between two byte offsets into
+/// the argument with surrounding whitespace removed.
+fn part(argument: Spanned<&str>, byte_start: usize, byte_end: usize) -> Spanned<&str> {
+	let slice = &argument.node[byte_start..byte_end];
+	let byte_start = argument.span.byte_start() + byte_start +
+		(slice.len() - slice.trim_start().len());
+	let slice = slice.trim();
+	Spanned::new(slice, Span(byte_start, byte_start + slice.len().max(1)))
+}
+
+/// The span immediately after an argument.
+fn end(argument: Spanned<&str>) -> Spanned<&str> {
+	let byte_end = argument.span.byte_start() + argument.node.len();
+	Spanned::new("", Span(byte_end, byte_end + 1))
+}
+
+/// Takes the remainder of an expression after a keyword
+/// with surrounding whitespace removed.
+fn argument(expression: &str, byte_start: usize) -> Spanned<&str> {
+	let remainder = &expression[byte_start..];
+	let byte_start = byte_start + (remainder.len() - remainder.trim_start().len());
+	let argument = expression[byte_start..].trim_end();
+	let byte_end = byte_start + argument.len().max(1);
+	Spanned::new(argument, Span(byte_start, byte_end))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_mode() {
+		let command = |string| super::command(string).map(|command|
+			command.map_err(|error| error.node));
+		assert_eq!(command("mode degrees"), Some(Ok(Command::Mode(AngleUnit::Degrees))));
+		assert_eq!(command(" mode  grad "), Some(Ok(Command::Mode(AngleUnit::Gradians))));
+		assert_eq!(command("mode"), Some(Err(Error::InvalidAngleUnit)));
+		assert_eq!(command("1 + 2"), None);
+	}
+
+	#[test]
+	fn test_rounding() {
+		let command = |string| super::command(string).map(|command|
+			command.map_err(|error| error.node));
+		assert_eq!(command("rounding half-even"), Some(Ok(Command::Rounding(Rounding::HalfEven))));
+		assert_eq!(command("rounding up"), Some(Err(Error::InvalidRounding)));
+	}
+
+	#[test]
+	fn test_expression() {
+		let command = super::command("factor 2 + 3").unwrap().unwrap();
+		let Command::Factor(node) = command else { panic!() };
+		assert_eq!(node.span, Span(7, 12));
+		assert_eq!(super::command("factor").unwrap(), Err(Spanned::new(Error::ExpectedValued, Span(6, 7))));
+	}
+
+	#[test]
+	fn test_explain() {
+		let explain = |string| match super::command(string) {
+			Some(Ok(Command::Explain(node, tree))) => (node.span, tree),
+			_ => panic!(),
+		};
+
+		assert_eq!(explain("explain 1 + 2;"), (Span(8, 13), false));
+		assert_eq!(explain("explain tree 1 + 2"), (Span(13, 18), true));
+		assert!(super::command("explain treetop").unwrap().is_err());
+	}
+
+	#[test]
+	fn test_solve() {
+		let solve = |string| match super::command(string) {
+			Some(Ok(Command::Solve(solve))) => Ok(solve),
+			Some(Err(error)) => Err(error.node),
+			_ => panic!(),
+		};
+
+		let equation = solve("solve x^2 = 2 for x from 0 to 10").unwrap();
+		assert_eq!((equation.span, equation.variable.as_str()), (Span(6, 13), "x"));
+		assert!(matches!(equation.search, Some(Search::Interval(_, _))));
+		let equation = solve("solve cos t - t for t near 1").unwrap();
+		assert!(equation.right.is_none() && matches!(equation.search, Some(Search::Guess(_))));
+		assert_eq!(solve("solve x = 2").unwrap_err(), Error::ExpectedKeyword("for"));
+		assert_eq!(solve("solve x = 2 for x from 0").unwrap_err(), Error::ExpectedKeyword("to"));
+		assert_eq!(solve("solve x = 2 for 2").unwrap_err(), Error::ExpectedVariable);
+		assert_eq!(solve("solve y = 2 for x").unwrap_err(), Error::InvalidItem);
+	}
+
+	#[test]
+	fn test_differentiate() {
+		let differentiate = |string| match super::command(string) {
+			Some(Ok(Command::Differentiate(differentiate))) => Ok(differentiate),
+			Some(Err(error)) => Err(error),
+			_ => panic!(),
+		};
+
+		let derivative = differentiate("d/dx x^2 at 3").unwrap();
+		assert_eq!((derivative.expression.span, derivative.variable.as_str()), (Span(5, 8), "x"));
+		assert_eq!(derivative.point.unwrap().span, Span(12, 13));
+		assert!(differentiate("d/dt sin t").unwrap().point.is_none());
+		assert_eq!(differentiate("d/d x").unwrap_err(), Spanned::new(Error::ExpectedVariable, Span(3, 4)));
+		assert_eq!(differentiate("d/d2 x").unwrap_err().node, Error::ExpectedVariable);
+		assert_eq!(differentiate("d/dx y").unwrap_err().node, Error::InvalidItem);
+	}
+
+	#[test]
+	fn test_plot() {
+		let plot = |string| match super::command(string) {
+			Some(Ok(Command::Plot(plot))) => Ok(plot),
+			Some(Err(error)) => Err(error.node),
+			_ => panic!(),
+		};
+
+		let spans = |plot: Box<Plot>| plot.expressions.iter()
+			.map(|expression| expression.span).collect::<Vec<_>>();
+		assert_eq!(spans(plot("plot sin x from -pi to pi").unwrap()), [Span(5, 10)]);
+		let expressions = plot("plot sum(k, 1, 3, k * x), x ^ 2 from 0 to 1").unwrap();
+		assert_eq!(spans(expressions), [Span(5, 24), Span(26, 31)]);
+		assert_eq!(plot("plot sin x").unwrap_err(), Error::ExpectedKeyword("from"));
+		assert_eq!(plot("plot sin x from 0").unwrap_err(), Error::ExpectedKeyword("to"));
+		assert_eq!(plot("plot sin x, from 0 to 1").unwrap_err(), Error::ExpectedValued);
+		assert_eq!(plot("plot sin t from 0 to 1").unwrap_err(), Error::InvalidItem);
+	}
+
+	#[test]
+	fn test_table() {
+		let table = |string| match super::command(string) {
+			Some(Ok(Command::Table(table))) => Ok(table),
+			Some(Err(error)) => Err(error.node),
+			_ => panic!(),
+		};
+
+		let values = table("table x^2 + 1 for x in 0..10 step 0.5").unwrap();
+		assert_eq!((values.expression.span, values.variable.as_str()), (Span(6, 13), "x"));
+		assert_eq!((values.start.span, values.end.span), (Span(23, 24), Span(26, 28)));
+		assert!(values.step.is_some() && !values.csv);
+		let values = table("table csv sqrt t for t in -1 .. 1").unwrap();
+		assert!(values.step.is_none() && values.csv);
+		assert_eq!(table("table x").unwrap_err(), Error::ExpectedKeyword("for"));
+		assert_eq!(table("table x for x").unwrap_err(), Error::ExpectedKeyword("in"));
+		assert_eq!(table("table x for x in 0").unwrap_err(), Error::ExpectedKeyword(".."));
+		assert_eq!(table("table x for x in 0..").unwrap_err(), Error::ExpectedValued);
+	}
+}
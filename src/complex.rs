@@ -0,0 +1,81 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Complex {
+	pub real: f64,
+	pub imaginary: f64,
+}
+
+impl Complex {
+	pub fn new(real: f64, imaginary: f64) -> Self {
+		Complex { real, imaginary }
+	}
+
+	pub fn real(real: f64) -> Self {
+		Complex::new(real, 0.0)
+	}
+
+	pub fn magnitude(&self) -> f64 {
+		self.real.hypot(self.imaginary)
+	}
+
+	/// Creates a complex number from its magnitude and argument.
+	pub fn polar(magnitude: f64, argument: f64) -> Self {
+		Complex::new(magnitude * argument.cos(), magnitude * argument.sin())
+	}
+}
+
+impl Add for Complex {
+	type Output = Complex;
+
+	fn add(self, other: Complex) -> Complex {
+		Complex::new(self.real + other.real, self.imaginary + other.imaginary)
+	}
+}
+
+impl Sub for Complex {
+	type Output = Complex;
+
+	fn sub(self, other: Complex) -> Complex {
+		Complex::new(self.real - other.real, self.imaginary - other.imaginary)
+	}
+}
+
+impl Mul for Complex {
+	type Output = Complex;
+
+	fn mul(self, other: Complex) -> Complex {
+		Complex::new(self.real * other.real - self.imaginary * other.imaginary,
+			self.real * other.imaginary + self.imaginary * other.real)
+	}
+}
+
+impl Div for Complex {
+	type Output = Complex;
+
+	fn div(self, other: Complex) -> Complex {
+		let denominator = other.real * other.real + other.imaginary * other.imaginary;
+		Complex::new((self.real * other.real + self.imaginary * other.imaginary) / denominator,
+			(self.imaginary * other.real - self.real * other.imaginary) / denominator)
+	}
+}
+
+impl Neg for Complex {
+	type Output = Complex;
+
+	fn neg(self) -> Complex {
+		Complex::new(-self.real, -self.imaginary)
+	}
+}
+
+impl fmt::Display for Complex {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match (self.real, self.imaginary) {
+			(real, 0.0) => write!(f, "{}", real),
+			(0.0, imaginary) => write!(f, "{}i", imaginary),
+			(real, imaginary) if imaginary < 0.0 => write!(f, "{} - {}i", real, -imaginary),
+			(real, imaginary) => write!(f, "{} + {}i", real, imaginary),
+		}
+	}
+}
@@ -1,12 +1,23 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 use crate::error::Error;
+use crate::item::AngleUnit;
+use crate::numeric::Rounding;
+use crate::value::Value;
 
 #[derive(Debug, Default)]
 pub struct Context {
 	current_index: usize,
-	variables: HashMap<String, f64>,
+	variables: HashMap<String, Value>,
 	history: Vec<String>,
+	bindings: RefCell<Vec<(String, f64)>>,
+
+	pub ieee: bool,
+	pub estimates: bool,
+	pub explain: bool,
+	pub angle_unit: AngleUnit,
+	pub rounding: Rounding,
 
 	pub cursor_position: usize,
 	pub history_offset: usize,
@@ -14,7 +25,7 @@ pub struct Context {
 }
 
 impl Context {
-	pub fn push_value(&mut self, value: f64) -> usize {
+	pub fn push_value(&mut self, value: Value) -> usize {
 		loop {
 			let index_key = format!("{:x}", self.current_index);
 			if self.variables.contains_key(&index_key) {
@@ -28,17 +39,51 @@ impl Context {
 		}
 	}
 
-	pub fn variable(&self, variable: &str) -> Result<f64, Error> {
-		Ok(match variable.chars().all(|character| character == '$') {
+	pub fn variable(&self, variable: &str) -> Result<Value, Error> {
+		match variable.chars().all(|character| character == '$') {
 			false => self.variables.get(variable)
 				.ok_or_else(|| Error::UndefinedVariable(variable.to_owned())),
 			true => {
-				let index = self.current_index.checked_sub(variable.len() + 1)
-					.ok_or(Error::InvalidEvaluationOffset)?;
+				let index = self.index(variable)?;
 				self.variables.get(&format!("{:x}", index))
 					.ok_or(Error::InvalidEvaluationOffset)
 			}
-		}?.clone())
+		}.cloned()
+	}
+
+	/// Finds the index of the evaluation result that a variable refers to.
+	pub fn index(&self, variable: &str) -> Result<usize, Error> {
+		match variable.chars().all(|character| character == '$') {
+			true => self.current_index.checked_sub(variable.len() + 1)
+				.ok_or(Error::InvalidEvaluationOffset),
+			false => usize::from_str_radix(variable, 16).ok()
+				.filter(|index| *index < self.current_index)
+				.ok_or_else(|| Error::UndefinedVariable(variable.to_owned())),
+		}
+	}
+
+	/// Collects the evaluation results between two indices inclusively.
+	pub fn range(&self, start: usize, end: usize) -> Result<Vec<f64>, Error> {
+		(start..=end).map(|index| match self.variables.get(&format!("{:x}", index)) {
+			Some(Value::Number(number)) | Some(Value::Estimate(number, _)) => Ok(*number),
+			Some(_) => Err(Error::ExpectedNumber),
+			None => Err(Error::UndefinedVariable(format!("{:x}", index))),
+		}).collect()
+	}
+
+	/// Evaluates with a variable bound to a value. Bindings shadow
+	/// the bindings of the same variable in enclosing scopes.
+	pub fn bind<R>(&self, variable: &str, value: f64, evaluate: impl FnOnce() -> R) -> R {
+		self.bindings.borrow_mut().push((variable.to_owned(), value));
+		let evaluation = evaluate();
+		self.bindings.borrow_mut().pop();
+		evaluation
+	}
+
+	pub fn binding(&self, variable: &str) -> Option<f64> {
+		self.bindings.borrow().iter().rev()
+			.find(|(binding, _)| binding == variable)
+			.map(|(_, value)| *value)
 	}
 
 	pub fn push_history(&mut self, expression: String) {
@@ -0,0 +1,375 @@
+use crate::context::Context;
+use crate::error::{Diagnostic, Error};
+use crate::item::{Calculus, Function, Hyperbolic, Linear, Series, Statistic, Trigonometric};
+use crate::node::Node;
+use crate::simplify;
+use crate::span::{Span, Spanned};
+use crate::token::Operator;
+
+/// Differentiates a node with respect to a variable. Functions that
+/// are constant between integers have a derivative of zero and the
+/// gamma function is differentiated numerically at its argument.
+/// The angle unit of the context determines the derivatives of the
+/// trigonometric functions that do not specify a unit.
+pub fn derivative(node: &Spanned<Node>, variable: &str, context: &Context)
+                  -> Result<Spanned<Node>, Diagnostic> {
+	let span = node.span;
+	if !depends(&node.node, variable) {
+		return Ok(terminal(0.0, span));
+	}
+
+	let derivative = |node| derivative(node, variable, context);
+	Ok(match &node.node {
+		Node::Terminal(_) | Node::Variable(_) | Node::Range(_, _) => terminal(0.0, span),
+		Node::Identifier(_) => terminal(1.0, span),
+		Node::List(elements) => Spanned::new(Node::List(elements.iter()
+			.map(derivative).collect::<Result<_, _>>()?), span),
+		Node::Conditional(condition, consequent, alternative) =>
+			Spanned::new(Node::Conditional(condition.clone(), Box::new(derivative(consequent)?),
+				Box::new(derivative(alternative)?)), span),
+		Node::Operator(operator, left, right) => {
+			let (left, right) = (left.as_ref().clone(), right.as_ref().clone());
+			let (left_derivative, right_derivative) = (derivative(&left)?, derivative(&right)?);
+			match operator.node {
+				Operator::Add => add(left_derivative, right_derivative),
+				Operator::Minus => subtract(left_derivative, right_derivative),
+				Operator::Multiply | Operator::ElementMultiply => {
+					let multiply = |left, right| product(operator.node, left, right);
+					add(multiply(left_derivative, right.clone()), multiply(left, right_derivative))
+				}
+				Operator::Divide if !depends(&right.node, variable) =>
+					divide(left_derivative, right),
+				Operator::Divide => {
+					let numerator = subtract(multiply(left_derivative, right.clone()),
+						multiply(left, right_derivative));
+					divide(numerator, power(right, terminal(2.0, span)))
+				}
+				Operator::Modulo => {
+					let quotient = apply(Function::Truncate, vec![divide(left, right)], span);
+					subtract(left_derivative, multiply(right_derivative, quotient))
+				}
+				Operator::Power if !depends(&right.node, variable) => {
+					let exponent = subtract(right.clone(), terminal(1.0, span));
+					multiply(multiply(right, power(left, exponent)), left_derivative)
+				}
+				Operator::Power if !depends(&left.node, variable) => {
+					let logarithm = apply(Function::NaturalLogarithm, vec![left.clone()], span);
+					multiply(multiply(power(left, right), logarithm), right_derivative)
+				}
+				Operator::Power => {
+					let logarithm = apply(Function::NaturalLogarithm, vec![left.clone()], span);
+					let exponent = add(multiply(right_derivative, logarithm),
+						divide(multiply(right.clone(), left_derivative), left.clone()));
+					multiply(power(left, right), exponent)
+				}
+				Operator::Less | Operator::LessEqual | Operator::Greater |
+				Operator::GreaterEqual | Operator::Equal | Operator::NotEqual |
+				Operator::And | Operator::Or => terminal(0.0, span),
+			}
+		}
+		Node::Function(function, arguments) => {
+			let argument = arguments[0].clone();
+			let apply = |function, argument: &Spanned<Node>| apply(function, vec![argument.clone()], span);
+			let chain = |factor| Ok::<_, Diagnostic>(multiply(derivative(&argument)?, factor));
+			let reciprocal = |divisor| Ok::<_, Diagnostic>(divide(derivative(&argument)?, divisor));
+			let number = |value| terminal(value, span);
+			let square = |node| power(node, number(2.0));
+			match &function.node {
+				Function::Trigonometric(trigonometric, unit) => {
+					use Trigonometric::*;
+					let radians = number(unit.unwrap_or(context.angle_unit).radians(1.0));
+					let related = |function| apply(Function::Trigonometric(function, *unit), &argument);
+					match trigonometric {
+						Sine => chain(multiply(related(Cosine), radians))?,
+						Cosine => chain(negate(multiply(related(Sine), radians)))?,
+						Tangent => chain(multiply(square(related(Secant)), radians))?,
+						Secant => chain(multiply(multiply(related(Secant),
+							related(Tangent)), radians))?,
+						Cosecant => chain(negate(multiply(multiply(related(Cosecant),
+							related(Cotangent)), radians)))?,
+						Cotangent => chain(negate(multiply(square(related(Cosecant)), radians)))?,
+						InverseSine | InverseCosine => {
+							let root = apply(Function::SquareRoot,
+								&subtract(number(1.0), square(argument.clone())));
+							let derivative = reciprocal(multiply(radians, root))?;
+							match trigonometric {
+								InverseSine => derivative,
+								_ => negate(derivative),
+							}
+						}
+						InverseTangent | InverseCotangent => {
+							let derivative = reciprocal(multiply(radians,
+								add(number(1.0), square(argument.clone()))))?;
+							match trigonometric {
+								InverseTangent => derivative,
+								_ => negate(derivative),
+							}
+						}
+						InverseSecant | InverseCosecant => {
+							let root = apply(Function::SquareRoot,
+								&subtract(square(argument.clone()), number(1.0)));
+							let magnitude = apply(Function::AbsoluteValue, &argument);
+							let derivative = reciprocal(multiply(radians, multiply(magnitude, root)))?;
+							match trigonometric {
+								InverseSecant => derivative,
+								_ => negate(derivative),
+							}
+						}
+					}
+				}
+				Function::Hyperbolic(hyperbolic) => match hyperbolic {
+					Hyperbolic::Sine => chain(apply(Function::Hyperbolic(Hyperbolic::Cosine), &argument))?,
+					Hyperbolic::Cosine => chain(apply(Function::Hyperbolic(Hyperbolic::Sine), &argument))?,
+					Hyperbolic::Tangent => chain(subtract(number(1.0),
+						square(apply(Function::Hyperbolic(Hyperbolic::Tangent), &argument))))?,
+					Hyperbolic::InverseSine => reciprocal(apply(Function::SquareRoot,
+						&add(square(argument.clone()), number(1.0))))?,
+					Hyperbolic::InverseCosine => reciprocal(apply(Function::SquareRoot,
+						&subtract(square(argument.clone()), number(1.0))))?,
+					Hyperbolic::InverseTangent =>
+						reciprocal(subtract(number(1.0), square(argument.clone())))?,
+				},
+				Function::AbsoluteValue => chain(apply(Function::Sign, &argument))?,
+				Function::SquareRoot => reciprocal(multiply(number(2.0),
+					apply(Function::SquareRoot, &argument)))?,
+				Function::CubeRoot => reciprocal(multiply(number(3.0),
+					square(apply(Function::CubeRoot, &argument))))?,
+				Function::NaturalLogarithm => reciprocal(argument.clone())?,
+				Function::BinaryLogarithm => reciprocal(multiply(argument.clone(),
+					apply(Function::NaturalLogarithm, &number(2.0))))?,
+				Function::DecimalLogarithm => reciprocal(multiply(argument.clone(),
+					apply(Function::NaturalLogarithm, &number(10.0))))?,
+				Function::Gamma | Function::Factorial => {
+					// The argument of the function is replaced by the variable
+					// itself which is bound by the numerical derivative.
+					let function = apply(function.node.clone(), &Spanned::new(
+						Node::Identifier(variable.to_owned()), span));
+					let derivative = Function::Calculus(Calculus::Derivative, variable.to_owned());
+					chain(self::apply(derivative, vec![function, argument.clone()], span))?
+				}
+				Function::Fraction => derivative(&argument)?,
+				Function::UnaryMinus => negate(derivative(&argument)?),
+				Function::Statistic(statistic @ (Statistic::Mean | Statistic::Sum)) =>
+					apply(Function::Statistic(statistic.clone()), &derivative(&argument)?),
+				Function::Linear(Linear::Transpose) =>
+					apply(Function::Linear(Linear::Transpose), &derivative(&argument)?),
+				Function::Series(_, bound) if bound == variable => number(0.0),
+				Function::Series(series, bound) => {
+					let body = &arguments[2];
+					let terms = match series {
+						Series::Sum => derivative(body)?,
+						Series::Product => divide(derivative(body)?, body.clone()),
+					};
+
+					let function = Function::Series(Series::Sum, bound.clone());
+					let sum = self::apply(function, vec![arguments[0].clone(),
+						arguments[1].clone(), terms], span);
+					match series {
+						Series::Sum => sum,
+						Series::Product => multiply(node.clone(), sum),
+					}
+				}
+				Function::Calculus(Calculus::Integral, bound) => {
+					let (lower, upper) = (&arguments[1], &arguments[2]);
+					let at = |limit: &Spanned<Node>| substitute(&argument, bound, limit);
+					let limits = subtract(multiply(at(upper), derivative(upper)?),
+						multiply(at(lower), derivative(lower)?));
+					match bound != variable && depends(&argument.node, variable) {
+						false => limits,
+						true => add(limits, self::apply(function.node.clone(),
+							vec![derivative(&argument)?, lower.clone(), upper.clone()], span)),
+					}
+				}
+				Function::Calculus(Calculus::Derivative, bound) => {
+					let inner = self::derivative(&argument, bound, context)?;
+					derivative(&substitute(&inner, bound, &arguments[1]))?
+				}
+				Function::Floor | Function::Ceiling | Function::Round | Function::RoundPlaces |
+				Function::SignificantFigures | Function::Truncate | Function::Sign |
+				Function::Not => number(0.0),
+				// Functions of integers are not continuous in their arguments.
+				Function::Binomial | Function::Permutation | Function::GreatestCommonDivisor |
+				Function::LeastCommonMultiple | Function::IsPrime | Function::NextPrime |
+				Function::PowerModulo | Function::InverseModulo |
+				Function::Statistic(_) | Function::Roots | Function::Linear(_) =>
+					return Err(Diagnostic::new(Error::NonDifferentiable, function.span)),
+			}
+		}
+	})
+}
+
+/// Whether a node depends on a variable that is not bound within it.
+fn depends(node: &Node, variable: &str) -> bool {
+	match node {
+		Node::Terminal(_) | Node::Variable(_) | Node::Range(_, _) => false,
+		Node::Identifier(identifier) => identifier == variable,
+		Node::Function(function, arguments) => match &function.node {
+			Function::Series(_, bound) if bound == variable =>
+				arguments[..2].iter().any(|argument| depends(&argument.node, variable)),
+			Function::Calculus(_, bound) if bound == variable =>
+				arguments[1..].iter().any(|argument| depends(&argument.node, variable)),
+			_ => arguments.iter().any(|argument| depends(&argument.node, variable)),
+		},
+		Node::Operator(_, left, right) =>
+			depends(&left.node, variable) || depends(&right.node, variable),
+		Node::List(elements) => elements.iter().any(|element| depends(&element.node, variable)),
+		Node::Conditional(condition, consequent, alternative) =>
+			[condition, consequent, alternative].iter().any(|node| depends(&node.node, variable)),
+	}
+}
+
+/// Replaces each occurrence of a variable that is not bound within a node.
+fn substitute(node: &Spanned<Node>, variable: &str, value: &Spanned<Node>) -> Spanned<Node> {
+	let substitute = |node: &Spanned<Node>| substitute(node, variable, value);
+	let all = |nodes: &[Spanned<Node>]| nodes.iter().map(substitute).collect();
+	Spanned::new(match &node.node {
+		Node::Identifier(identifier) if identifier == variable => return value.clone(),
+		Node::Function(function, arguments) => Node::Function(function.clone(),
+			match &function.node {
+				Function::Series(_, bound) if bound == variable => arguments[..2].iter()
+					.map(substitute).chain(arguments[2..].iter().cloned()).collect(),
+				Function::Calculus(_, bound) if bound == variable => arguments[..1].iter()
+					.cloned().chain(arguments[1..].iter().map(substitute)).collect(),
+				_ => all(arguments),
+			}),
+		Node::Operator(operator, left, right) => Node::Operator(*operator,
+			Box::new(substitute(left)), Box::new(substitute(right))),
+		Node::List(elements) => Node::List(all(elements)),
+		Node::Conditional(condition, consequent, alternative) => Node::Conditional(
+			Box::new(substitute(condition)), Box::new(substitute(consequent)),
+			Box::new(substitute(alternative))),
+		node => node.clone(),
+	}, node.span)
+}
+
+/// Creates a number that is negated if it is negative.
+fn terminal(value: f64, span: Span) -> Spanned<Node> {
+	let terminal = Spanned::new(Node::Terminal(value.abs()), span);
+	match value < 0.0 {
+		false => terminal,
+		true => negate(terminal),
+	}
+}
+
+fn apply(function: Function, arguments: Vec<Spanned<Node>>, span: Span) -> Spanned<Node> {
+	Spanned::new(Node::Function(Spanned::new(function, span), arguments), span)
+}
+
+fn operator(operator: Operator, left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	let span = Span(left.span.byte_start().min(right.span.byte_start()),
+		left.span.byte_end().max(right.span.byte_end()));
+	let operator = Spanned::new(operator, span);
+	Spanned::new(Node::Operator(operator, Box::new(left), Box::new(right)), span)
+}
+
+fn is(node: &Spanned<Node>, value: f64) -> bool {
+	simplify::literal(&node.node) == Some(value)
+}
+
+fn add(left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	match (is(&left, 0.0), is(&right, 0.0)) {
+		(true, _) => right,
+		(_, true) => left,
+		_ => operator(Operator::Add, left, right),
+	}
+}
+
+fn subtract(left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	match (is(&left, 0.0), is(&right, 0.0)) {
+		(_, true) => left,
+		(true, _) => negate(right),
+		_ => operator(Operator::Minus, left, right),
+	}
+}
+
+fn multiply(left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	product(Operator::Multiply, left, right)
+}
+
+/// Multiplies two nodes with the operator of a product. A product with
+/// zero is zero regardless of whether the other factor is a number.
+fn product(product: Operator, left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	match (&left, &right) {
+		(zero, _) | (_, zero) if is(zero, 0.0) => terminal(0.0, zero.span),
+		(one, other) | (other, one) if is(one, 1.0) => other.clone(),
+		_ => operator(product, left, right),
+	}
+}
+
+fn divide(left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	match (is(&left, 0.0), is(&right, 1.0)) {
+		(true, _) | (_, true) => left,
+		_ => operator(Operator::Divide, left, right),
+	}
+}
+
+fn power(left: Spanned<Node>, right: Spanned<Node>) -> Spanned<Node> {
+	match is(&right, 1.0) {
+		true => left,
+		false => operator(Operator::Power, left, right),
+	}
+}
+
+fn negate(node: Spanned<Node>) -> Spanned<Node> {
+	let span = node.span;
+	match node.node {
+		Node::Terminal(0.0) => node,
+		Node::Function(function, mut arguments) if function.node == Function::UnaryMinus =>
+			arguments.pop().unwrap(),
+		node => apply(Function::UnaryMinus, vec![Spanned::new(node, span)], span),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::item::AngleUnit;
+	use crate::tests::parse;
+
+	fn derived(expression: &str, context: &Context) -> String {
+		let derivative = derivative(&parse(expression, &["x"]), "x", context).unwrap();
+		crate::notation::formatted(&simplify::simplify(&derivative, context).node)
+	}
+
+	#[test]
+	fn test_derivative() {
+		let derived = |expression| derived(expression, &Context::default());
+		assert_eq!(derived("x ^ 2"), "2 * x");
+		assert_eq!(derived("3 * x + 2"), "3");
+		assert_eq!(derived("x * sin x"), "sin x + x * cos x");
+		assert_eq!(derived("sin (x ^ 2)"), "2 * x * cos (x ^ 2)");
+		assert_eq!(derived("ln (x ^ 2 + 1)"), "2 * x / (x ^ 2 + 1)");
+		assert_eq!(derived("e ^ x"), "e ^ x");
+		assert_eq!(derived("1 / x"), "-1 / x ^ 2");
+		assert_eq!(derived("x ^ x"), "x ^ x * (ln x + x / x)");
+		assert_eq!(derived("gamma x"), "deriv(gamma x, x)");
+		assert_eq!(derived("floor x + $"), "0");
+		assert_eq!(derived("sum(k, 1, 3, k * x ^ 2)"), "sum(k, 1, 3, k * (2 * x))");
+		assert_eq!(derived("integrate(x * t, 0, x)"), "x * x + integrate(t, 0, x)");
+	}
+
+	#[test]
+	fn test_angle_unit() {
+		let context = &mut Context::default();
+		assert_eq!(derived("sin'r x", context), "cos'r x");
+		context.angle_unit = AngleUnit::Degrees;
+		assert_eq!(derived("sin x", context), format!("cos x * {}", 1f64.to_radians()));
+	}
+
+	#[test]
+	fn test_point() {
+		let context = &Context::default();
+		let value = |expression: &str, point| {
+			let derivative = derivative(&parse(expression, &["x"]), "x", context)?;
+			context.bind("x", point, || derivative.number(context))
+		};
+
+		assert_eq!(value("x ^ 2", 3.0).unwrap(), 6.0);
+		assert_eq!(value("if x < 0 then -x else x ^ 3", -2.0).unwrap(), -1.0);
+		assert!((value("gamma x", 1.0).unwrap() + 0.5772).abs() < 1e-3);
+		assert_eq!(value("median [x, 1]", 1.0).unwrap_err().error.node, Error::NonDifferentiable);
+		assert_eq!(value("ncr x 2", 4.0).unwrap_err().error.node, Error::NonDifferentiable);
+		assert_eq!(value("gcd 12 x", 4.0).unwrap_err().error.node, Error::NonDifferentiable);
+		assert_eq!(value("round x", 1.2).unwrap(), 0.0);
+	}
+}
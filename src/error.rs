@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::span::{Span, Spanned};
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
 	UndefinedVariable(String),
@@ -14,6 +16,30 @@ pub enum Error {
 	InvalidCoalesce,
 	ZeroDivision,
 	NegativeRoot,
+	Domain,
+	Overflow,
+	NegativeFactorial,
+	NonInteger,
+	InvalidAngleUnit,
+	InvalidRounding,
+	InexactInteger,
+	NoInverse,
+	ExpectedNumber,
+	ExpectedList,
+	ExpectedVariable,
+	InvalidRange,
+	MisplacedComma,
+	ExpectedArguments(usize),
+	IterationLimit,
+	ExpectedKeyword(&'static str),
+	NoConvergence,
+	NoRoot,
+	ExpectedMatrix,
+	DimensionMismatch,
+	NonSquare,
+	SingularMatrix,
+	MisplacedKeyword,
+	NonDifferentiable,
 }
 
 impl fmt::Display for Error {
@@ -43,8 +69,90 @@ impl fmt::Display for Error {
 				write!(f, "Division by zero"),
 			Error::NegativeRoot =>
 				write!(f, "Negative root is undefined"),
+			Error::Domain =>
+				write!(f, "Argument is outside the function domain"),
+			Error::Overflow =>
+				write!(f, "Result is too large to represent"),
+			Error::NegativeFactorial =>
+				write!(f, "Factorial of a negative integer is undefined"),
+			Error::NonInteger =>
+				write!(f, "Expected an integer"),
+			Error::InvalidAngleUnit =>
+				write!(f, "Expected radians, degrees or gradians"),
+			Error::InvalidRounding =>
+				write!(f, "Expected half-up or half-even"),
+			Error::InexactInteger =>
+				write!(f, "Integer is too large to be exact"),
+			Error::NoInverse =>
+				write!(f, "Value has no modular inverse"),
+			Error::ExpectedNumber =>
+				write!(f, "Expected a number"),
+			Error::ExpectedList =>
+				write!(f, "Expected a list"),
+			Error::ExpectedVariable =>
+				write!(f, "Expected a variable"),
+			Error::InvalidRange =>
+				write!(f, "Range starts after it ends"),
+			Error::MisplacedComma =>
+				write!(f, "Comma is outside of a list"),
+			Error::ExpectedArguments(count) =>
+				write!(f, "Expected {} arguments", count),
+			Error::IterationLimit =>
+				write!(f, "Too many iterations to evaluate"),
+			Error::ExpectedKeyword(keyword) =>
+				write!(f, "Expected keyword: {}", keyword),
+			Error::NoConvergence =>
+				write!(f, "Iterations did not converge to a root"),
+			Error::NoRoot =>
+				write!(f, "No root was found"),
+			Error::ExpectedMatrix =>
+				write!(f, "Expected a matrix"),
+			Error::DimensionMismatch =>
+				write!(f, "Dimensions do not match"),
+			Error::NonSquare =>
+				write!(f, "Matrix is not square"),
+			Error::SingularMatrix =>
+				write!(f, "Matrix is singular"),
+			Error::MisplacedKeyword =>
+				write!(f, "Keyword is outside of a conditional"),
+			Error::NonDifferentiable =>
+				write!(f, "Function has no symbolic derivative"),
 		}
 	}
 }
 
 impl std::error::Error for Error {}
+
+/// An error with an optional secondary span that labels a related term.
+#[derive(Debug)]
+pub struct Diagnostic {
+	pub error: Spanned<Error>,
+	pub secondary: Option<Spanned<String>>,
+}
+
+impl Diagnostic {
+	pub fn new(error: Error, span: Span) -> Self {
+		Spanned::new(error, span).into()
+	}
+
+	pub fn label(mut self, span: Span, label: String) -> Self {
+		self.secondary = Some(Spanned::new(label, span));
+		self
+	}
+}
+
+impl From<Spanned<Error>> for Diagnostic {
+	fn from(error: Spanned<Error>) -> Self {
+		Diagnostic { error, secondary: None }
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.error.node)?;
+		match &self.secondary {
+			Some(secondary) => write!(f, " (= {})", secondary.node),
+			None => Ok(()),
+		}
+	}
+}
@@ -0,0 +1,115 @@
+use crate::item::Function;
+use crate::node::Node;
+use crate::numeric;
+use crate::span::Spanned;
+
+/// Writes a node with every compound term in parentheses
+/// such that the grouping of every operator is explicit.
+pub fn parenthesised(node: &Node) -> String {
+	match node {
+		Node::Terminal(terminal) => numeric::plain(*terminal),
+		Node::Variable(variable) => format!("${}", variable),
+		Node::Identifier(identifier) => identifier.clone(),
+		Node::Range(start, end) => format!("${}..${}", start.node, end.node),
+		Node::List(elements) => format!("[{}]", elements.iter()
+			.map(|element| parenthesised(&element.node)).collect::<Vec<_>>().join(", ")),
+		Node::Operator(operator, left, right) => format!("{} {} {}", term(&left.node),
+			operator.node.definition().symbol, term(&right.node)),
+		Node::Conditional(condition, consequent, alternative) =>
+			format!("if {} then {} else {}", parenthesised(&condition.node),
+				parenthesised(&consequent.node), parenthesised(&alternative.node)),
+		Node::Function(function, arguments) => match &function.node {
+			Function::Series(_, variable) => format!("{}({}, {})", function.node,
+				variable, list(arguments)),
+			Function::Calculus(_, variable) if !variable.is_empty() =>
+				format!("{}({})", function.node, list(arguments)),
+			Function::Factorial => format!("{}!", term(&arguments[0].node)),
+			Function::UnaryMinus => format!("-{}", term(&arguments[0].node)),
+			_ => arguments.iter().fold(function.node.to_string(), |string, argument|
+				string + " " + &term(&argument.node)),
+		},
+	}
+}
+
+/// Writes a node that is an operand or an argument.
+fn term(node: &Node) -> String {
+	match compound(node) {
+		true => format!("({})", parenthesised(node)),
+		false => parenthesised(node),
+	}
+}
+
+/// Writes the arguments of a function that are separated by commas.
+fn list(arguments: &[Spanned<Node>]) -> String {
+	arguments.iter().map(|argument| parenthesised(&argument.node))
+		.collect::<Vec<_>>().join(", ")
+}
+
+/// Whether a node must be enclosed in parentheses to be a term.
+pub fn compound(node: &Node) -> bool {
+	match node {
+		Node::Operator(_, _, _) | Node::Conditional(_, _, _) => true,
+		Node::Function(function, _) => !matches!(&function.node, Function::Series(_, _)) &&
+			!matches!(&function.node, Function::Calculus(_, variable) if !variable.is_empty()),
+		_ => false,
+	}
+}
+
+/// Draws a node as a tree with each operator or
+/// function above its operands or arguments.
+pub fn tree(node: &Node) -> String {
+	let mut lines = Vec::new();
+	branch(node, String::new(), String::new(), &mut lines);
+	lines.join("\n")
+}
+
+fn branch(node: &Node, head: String, indent: String, lines: &mut Vec<String>) {
+	let (label, children): (String, Vec<&Spanned<Node>>) = match node {
+		Node::Operator(operator, left, right) => (operator.node.definition().symbol
+			.to_owned(), vec![left, right]),
+		Node::Function(function, arguments) => (match &function.node {
+			Function::Series(_, variable) | Function::Calculus(_, variable)
+				if !variable.is_empty() => format!("{} {}", function.node, variable),
+			function => function.to_string(),
+		}, arguments.iter().collect()),
+		Node::List(elements) => ("[]".to_owned(), elements.iter().collect()),
+		Node::Conditional(condition, consequent, alternative) =>
+			("if".to_owned(), vec![condition, consequent, alternative]),
+		node => (parenthesised(node), Vec::new()),
+	};
+
+	lines.push(head + &label);
+	for (index, child) in children.iter().enumerate() {
+		let (head, tail) = match index + 1 == children.len() {
+			false => ("├── ", "│   "),
+			true => ("└── ", "    "),
+		};
+		branch(&child.node, indent.clone() + head, indent.clone() + tail, lines);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn node(expression: &str) -> Node {
+		crate::tests::parse(expression, &[]).node
+	}
+
+	#[test]
+	fn test_parenthesised() {
+		let explain = |expression| parenthesised(&node(expression));
+		assert_eq!(explain("1 / 2 - 3 + 4; ;"), "1 / (2 - (3 + 4))");
+		assert_eq!(explain("10 - 2 + 4"), "(10 - 2) + 4");
+		assert_eq!(explain("-2 ^ 2 + sqrt 4!"), "(-(2 ^ 2)) + (sqrt (4!))");
+		assert_eq!(explain("sin' -90 * ncr 5 2"), "(sin' (-90)) * (ncr 5 2)");
+		assert_eq!(explain("sum(k, 1, 10, k ^ 2) + [1, 2 * $]"), "sum(k, 1, 10, k ^ 2) + [1, 2 * $]");
+		assert_eq!(explain("if 1 < 2 then 3 else 4"), "if 1 < 2 then 3 else 4");
+		assert_eq!(explain("1e-300 * 2e20 + 0.5"), "(1e-300 * 2e20) + 0.5");
+	}
+
+	#[test]
+	fn test_tree() {
+		assert_eq!(tree(&node("1 - 2 * -3")), "-\n├── 1\n└── *\n    ├── 2\n    └── -\n        └── 3");
+	}
+}
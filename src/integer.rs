@@ -0,0 +1,161 @@
+/// The largest magnitude below which every integer is exactly representable.
+pub const MAXIMUM_EXACT: f64 = 9_007_199_254_740_992.0;
+
+/// Bases that make the Miller-Rabin test deterministic for every `u64`.
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+pub fn gcd(mut left: u64, mut right: u64) -> u64 {
+	while right != 0 {
+		let remainder = left % right;
+		left = right;
+		right = remainder;
+	}
+	left
+}
+
+pub fn lcm(left: u64, right: u64) -> Option<u64> {
+	match gcd(left, right) {
+		0 => Some(0),
+		divisor => (left / divisor).checked_mul(right),
+	}
+}
+
+fn multiply_modulo(left: u64, right: u64, modulus: u64) -> u64 {
+	(left as u128 * right as u128 % modulus as u128) as u64
+}
+
+pub fn power_modulo(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+	let mut result = 1 % modulus;
+	base %= modulus;
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = multiply_modulo(result, base, modulus);
+		}
+
+		base = multiply_modulo(base, base, modulus);
+		exponent >>= 1;
+	}
+	result
+}
+
+/// Finds the inverse of a value modulo the modulus with
+/// the extended Euclidean algorithm if the inverse exists.
+pub fn inverse_modulo(value: i64, modulus: i64) -> Option<i64> {
+	let (mut previous, mut current) = (value.rem_euclid(modulus), modulus);
+	let (mut previous_coefficient, mut coefficient) = (1i64, 0i64);
+	while current != 0 {
+		let quotient = previous / current;
+		(previous, current) = (current, previous - quotient * current);
+		(previous_coefficient, coefficient) =
+			(coefficient, previous_coefficient - quotient * coefficient);
+	}
+
+	match previous {
+		1 => Some(previous_coefficient.rem_euclid(modulus)),
+		_ => None,
+	}
+}
+
+/// Tests primality with a deterministic Miller-Rabin test.
+pub fn is_prime(value: u64) -> bool {
+	if value < 2 {
+		return false;
+	} else if let Some(witness) = WITNESSES.iter().find(|witness| value.is_multiple_of(**witness)) {
+		return value == *witness;
+	}
+
+	let shift = (value - 1).trailing_zeros();
+	let odd = (value - 1) >> shift;
+	WITNESSES.iter().all(|witness| {
+		let mut power = power_modulo(*witness, odd, value);
+		if power == 1 || power == value - 1 {
+			return true;
+		}
+
+		(1..shift).any(|_| {
+			power = multiply_modulo(power, power, value);
+			power == value - 1
+		})
+	})
+}
+
+/// Finds the smallest prime that is greater than the value.
+pub fn next_prime(value: u64) -> u64 {
+	(value + 1..).find(|candidate| is_prime(*candidate)).unwrap()
+}
+
+/// Finds the prime factors of a value with their multiplicities
+/// in ascending order of the factors.
+pub fn factorize(value: u64) -> Vec<(u64, u32)> {
+	let mut factors = Vec::new();
+	divisors(value, &mut factors);
+	factors.sort_unstable();
+
+	let mut multiplicities: Vec<(u64, u32)> = Vec::new();
+	for factor in factors {
+		match multiplicities.last_mut() {
+			Some((last, count)) if *last == factor => *count += 1,
+			_ => multiplicities.push((factor, 1)),
+		}
+	}
+	multiplicities
+}
+
+fn divisors(value: u64, factors: &mut Vec<u64>) {
+	if value < 2 {
+		return;
+	} else if is_prime(value) {
+		return factors.push(value);
+	} else if value.is_multiple_of(2) {
+		factors.push(2);
+		return divisors(value / 2, factors);
+	}
+
+	let divisor = pollard_rho(value);
+	divisors(divisor, factors);
+	divisors(value / divisor, factors);
+}
+
+/// Finds a non-trivial divisor of an odd composite value.
+fn pollard_rho(value: u64) -> u64 {
+	(1..).find_map(|constant| {
+		let step = |x: u64| (multiply_modulo(x, x, value) + constant) % value;
+		let (mut tortoise, mut hare, mut divisor) = (2, 2, 1);
+		while divisor == 1 {
+			tortoise = step(tortoise);
+			hare = step(step(hare));
+			divisor = gcd(tortoise.max(hare) - tortoise.min(hare), value);
+		}
+
+		Some(divisor).filter(|divisor| *divisor != value)
+	}).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_primality() {
+		let primes: Vec<_> = (0..30).filter(|value| is_prime(*value)).collect();
+		assert_eq!(primes, &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+		assert!(is_prime(9_007_199_254_740_881));
+		assert!(!is_prime(3_215_031_751));
+		assert_eq!(next_prime(13), 17);
+	}
+
+	#[test]
+	fn test_factorize() {
+		assert_eq!(factorize(360), &[(2, 3), (3, 2), (5, 1)]);
+		assert_eq!(factorize(600_851_475_143), &[(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+		assert_eq!(factorize(1), &[]);
+	}
+
+	#[test]
+	fn test_modular() {
+		assert_eq!(power_modulo(2, 10, 1000), 24);
+		assert_eq!(inverse_modulo(3, 11), Some(4));
+		assert_eq!(inverse_modulo(-3, 11), Some(7));
+		assert_eq!(inverse_modulo(2, 4), None);
+	}
+}
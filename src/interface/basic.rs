@@ -2,36 +2,57 @@ use std::io::{Read, stdin, stdout, Write};
 
 use crossterm::{Colorize, Styler};
 
+use crate::command::Command;
 use crate::context::Context;
-use crate::error::Error;
-use crate::span::{Span, Spanned};
+use crate::error::Diagnostic;
+use crate::node::Node;
+use crate::span::Spanned;
+use crate::value::Value;
 
-pub fn basic() -> super::Result {
-	print!("{}", super::PROMPT.white().bold());
+pub fn basic(context: &mut Context) -> super::Result {
+	print!("{}", super::prompt(context).white().bold());
 	stdout().flush()?;
 
-	let context = &mut Context::default();
 	while let Ok(count) = stdin().read_line(&mut context.expression) {
 		if count == 0 {
 			break;
 		}
 
-		match evaluate(context) {
-			Ok(evaluation) => {
-				let index = context.push_value(evaluation);
-				super::render::value_index(index);
-				super::render::evaluation(evaluation, None);
-				println!();
-			}
-			Err(error) => {
-				let Span(byte_start, byte_end) = error.span;
-				let specific = "^".repeat(byte_end - byte_start).to_owned();
-				eprintln!("{}{} {}", " ".repeat(super::PROMPT.len() + byte_start),
-					specific, error.node);
-			}
+		match crate::command::command(&context.expression) {
+			Some(Ok(Command::Table(table))) if !table.csv => match super::command::rows(context, &table) {
+				Ok(rows) => {
+					let expression = crate::notation::formatted(&table.expression.node);
+					super::render::table((&table.variable, &expression), &rows, || {
+						println!();
+						Ok(())
+					})?;
+					println!();
+				}
+				Err(error) => self::error(&error),
+			},
+			Some(Ok(command)) => match super::command::execute(context, command) {
+				Ok(output) => output.iter().for_each(|output| println!("{}", output)),
+				Err(error) => self::error(&error),
+			},
+			Some(Err(error)) => self::error(&error.into()),
+			None => match parse(context).and_then(|node| explain(context, node)) {
+				Ok(evaluation) => {
+					let index = context.push_value(evaluation.clone());
+					let indent = super::render::value_index(index);
+					match &evaluation {
+						Value::Matrix(matrix) => super::render::grid(matrix, indent, || {
+							println!();
+							Ok(())
+						})?,
+						_ => super::render::value(&evaluation, None, context.estimates),
+					}
+					println!();
+				}
+				Err(error) => self::error(&error),
+			},
 		}
 
-		print!("{}", super::PROMPT.white().bold());
+		print!("{}", super::prompt(context).white().bold());
 		context.expression.clear();
 		stdout().flush()?;
 	}
@@ -39,16 +60,38 @@ pub fn basic() -> super::Result {
 	Ok(())
 }
 
-pub fn evaluate_direct() -> super::Result {
-	let context = &mut Context::default();
+fn error(error: &Diagnostic) {
+	let (byte_start, underline) = super::render::underline(error);
+	eprintln!("{}{} {}", " ".repeat(super::PROMPT.len() + byte_start),
+		underline, error);
+}
+
+/// Evaluates a node after printing its grouping if explanations are enabled.
+fn explain(context: &Context, node: Spanned<Node>) -> Result<Value, Diagnostic> {
+	if context.explain {
+		println!("{}", crate::explain::parenthesised(&node.node));
+	}
+	node.evaluate(context)
+}
+
+pub fn evaluate_direct(context: &mut Context) -> super::Result {
 	stdin().read_to_string(&mut context.expression)?;
-	println!("{}", evaluate(context).map_err(|error| error.node)?);
+	let evaluation = parse(context).and_then(|node| explain(context, node));
+	match evaluation.map_err(|error| error.error.node)? {
+		Value::Estimate(evaluation, error) if context.estimates =>
+			println!("{} ± {}", evaluation, error),
+		evaluation => println!("{}", evaluation),
+	}
 	Ok(())
 }
 
-pub fn evaluate(context: &mut Context) -> Result<f64, Spanned<Error>> {
+#[cfg(test)]
+pub fn evaluate(context: &mut Context) -> Result<Value, Diagnostic> {
+	parse(context)?.evaluate(context)
+}
+
+fn parse(context: &Context) -> Result<Spanned<Node>, Diagnostic> {
 	let lexer = &mut crate::lexer::Lexer::new(&context.expression);
 	let coalescence = crate::coalesce::coalesce_root(lexer)?;
-	let node = crate::parse::parse_root(coalescence);
-	node.evaluate(context)
+	Ok(crate::parse::parse_root(coalescence))
 }
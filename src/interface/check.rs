@@ -13,7 +13,7 @@ pub fn check(context: &Context) -> CheckResult {
 	let lexer = &mut crate::lexer::Lexer::new(&context.expression);
 	let coalescence = crate::coalesce::coalesce_root(lexer);
 	if let Err(error) = coalescence {
-		render::line_error(&error)?;
+		render::line_error(&error.into())?;
 		return Ok(None);
 	}
 
@@ -21,16 +21,20 @@ pub fn check(context: &Context) -> CheckResult {
 	coalesce_anchors(&coalescence)?;
 
 	if let Err(error) = coalescence.verify(context) {
-		render::line_error(&error)?;
+		render::line_error(&error.into())?;
 		return Ok(None);
 	}
 
 	Ok(Some(coalescence))
 }
 
+/// Marks the start of each term with an anchor and underlines each
+/// group of terms with a bracket on the line for its depth of nesting.
 pub fn coalesce_anchors(coalescence: &Coalescence) -> super::Result {
 	render::clear_buffer()?;
 	let anchors = coalescence.coalesce_anchors();
+	let groups = coalescence.coalesce_groups();
+	render::buffer_lines(groups.len() as u16 + 1)?;
 	queue!(stdout(), SavePos, Down(1), SetFg(Color::Yellow))?;
 
 	let (_, row) = crossterm::cursor().pos()?;
@@ -38,17 +42,20 @@ pub fn coalesce_anchors(coalescence: &Coalescence) -> super::Result {
 		Goto((super::PROMPT.len() + offset) as u16, row),
 		Output("^".to_string())))?;
 
-	if let Some(offset) = anchors.last() {
-		let byte_end = coalescence.byte_end();
-		render::anchor_start(match byte_end == *offset + 1 {
-			false => *offset + 1,
-			true => *offset,
-		})?;
-
-		let coalesce_length = (coalescence.byte_end() - *offset).saturating_sub(2);
-		queue!(stdout(), Output("-".repeat(coalesce_length)), Output("^".to_string()))?;
+	for (depth, spans) in groups.iter().enumerate() {
+		let row = row + depth as u16 + 1;
+		spans.iter().try_for_each(|span| queue!(stdout(),
+			Goto((super::PROMPT.len() + span.byte_start()) as u16, row),
+			Output(bracket(span.byte_end() - span.byte_start()))))?;
 	}
 
 	Ok(queue!(stdout(), SetFg(Color::Reset), ResetPos)?)
 }
 
+/// Draws a bracket that underlines the given number of characters.
+fn bracket(length: usize) -> String {
+	match length {
+		0 | 1 => "^".to_string(),
+		_ => format!("└{}┘", "─".repeat(length - 2)),
+	}
+}
@@ -0,0 +1,235 @@
+use crate::bytecode;
+use crate::command::{Command, Differentiate, Plot, Search, Solve, Table};
+use crate::context::Context;
+use crate::derivative;
+use crate::error::{Diagnostic, Error};
+use crate::explain;
+use crate::integer;
+use crate::notation;
+use crate::numeric::{self, Rounding};
+use crate::root;
+use crate::simplify;
+use crate::span::Span;
+
+use super::plot::{self, Style};
+
+/// The width and height of the curves of a plot in text.
+const TEXT_SIZE: (usize, usize) = (72, 20);
+
+/// The largest number of rows that a table can have.
+const MAXIMUM_ROWS: f64 = 10_000.0;
+
+/// The interval that is searched for roots if no interval or guess is given.
+const SEARCH_INTERVAL: (f64, f64) = (-100.0, 100.0);
+
+/// Executes a command and returns the output of the command.
+pub fn execute(context: &mut Context, command: Command) -> Result<Option<String>, Diagnostic> {
+	Ok(match command {
+		Command::Mode(unit) => {
+			context.angle_unit = unit;
+			None
+		}
+		Command::Rounding(rounding) => {
+			context.rounding = rounding;
+			None
+		}
+		Command::Factor(node) => {
+			let value = node.number(context)?;
+			let error = |error| Err(Diagnostic::new(error, node.span));
+			if value.abs() > integer::MAXIMUM_EXACT {
+				return error(Error::InexactInteger);
+			} else if !numeric::is_integer(value) {
+				return error(Error::NonInteger);
+			} else if value == 0.0 {
+				return error(Error::Domain);
+			}
+
+			Some(factorization(value as i64))
+		}
+		Command::Solve(solve) => Some(self::solve(context, &solve)?),
+		Command::Explain(node, false) => Some(explain::parenthesised(&node.node)),
+		Command::Explain(node, true) => Some(explain::tree(&node.node)),
+		Command::Coalesce(node) => Some(notation::coalesced(&node.node)),
+		Command::Simplify(node) =>
+			Some(notation::formatted(&simplify::simplify(&node, context).node)),
+		Command::Differentiate(differentiate) => Some(self::differentiate(context, &differentiate)?),
+		Command::Plot(plot) => Some(self::plot(context, &plot, Style::Text, TEXT_SIZE)?),
+		Command::Table(table) => Some(csv(&table, &rows(context, &table)?)),
+	})
+}
+
+/// A value of the variable of a table with the value of its expression.
+pub type Row = (f64, Result<f64, Diagnostic>);
+
+/// Evaluates the expression of a table at each value of its variable.
+/// The error of each row that cannot be evaluated is kept in its place.
+pub fn rows(context: &Context, table: &Table) -> Result<Vec<Row>, Diagnostic> {
+	let (start, end) = (table.start.number(context)?, table.end.number(context)?);
+	let span = Span(table.start.span.byte_start(), table.end.span.byte_end());
+	if start > end {
+		return Err(Diagnostic::new(Error::InvalidRange, span));
+	}
+
+	let step = match &table.step {
+		None => 1.0,
+		Some(node) => match node.number(context)? {
+			step if step > 0.0 => step,
+			step => return Err(Diagnostic::new(Error::Domain, node.span)
+				.label(node.span, step.to_string())),
+		},
+	};
+
+	// The count is rounded so that the end is included despite rounding errors.
+	let count = ((end - start) / step * 1e9).round() / 1e9;
+	if count + 1.0 > MAXIMUM_ROWS {
+		return Err(Diagnostic::new(Error::IterationLimit, span)
+			.label(span, (count.floor() + 1.0).to_string()));
+	}
+
+	// Values are rounded to remove the errors of adding the step to the start.
+	let magnitude = start.abs().max(end.abs()).max(step);
+	let places = 14 - magnitude.log10().floor() as i64;
+	let value = |index: usize| numeric::round(start + index as f64 * step, places, Rounding::HalfEven);
+	let expression = &simplify::simplify(&table.expression, context);
+	let function = bytecode::function(expression, &table.variable, context);
+	Ok((0..=count as usize).map(value).map(|value| (value, function(value))).collect())
+}
+
+/// Writes the rows of a table as comma separated values.
+fn csv(table: &Table, rows: &[Row]) -> String {
+	let field = |string: String| match string.contains([',', '"']) {
+		false => string,
+		true => format!("\"{}\"", string.replace('"', "\"\"")),
+	};
+
+	let mut lines = vec![format!("{},{}", table.variable,
+		field(notation::formatted(&table.expression.node)))];
+	lines.extend(rows.iter().map(|(value, output)| format!("{},{}", value, match output {
+		Ok(output) => output.to_string(),
+		Err(error) => field(error.to_string()),
+	})));
+	lines.join("\n")
+}
+
+/// Plots the expressions of `x` over an interval.
+pub fn plot(context: &Context, plot: &Plot, style: Style, size: (usize, usize))
+            -> Result<String, Diagnostic> {
+	let (lower, upper) = (plot.lower.number(context)?, plot.upper.number(context)?);
+	if lower >= upper {
+		let span = Span(plot.lower.span.byte_start(), plot.upper.span.byte_end());
+		return Err(Diagnostic::new(Error::InvalidRange, span));
+	}
+
+	let simplified: Vec<_> = plot.expressions.iter()
+		.map(|expression| simplify::simplify(expression, context)).collect();
+	let functions: Vec<_> = plot.expressions.iter().zip(&simplified).map(|(expression, simplified)|
+		(notation::formatted(&expression.node), bytecode::function(simplified, "x", context)))
+		.collect();
+	Ok(plot::plot(&functions, (lower, upper), size, style)?.join("\n"))
+}
+
+/// Formats the simplified derivative of an expression or
+/// the value of the derivative if a point is given.
+fn differentiate(context: &Context, differentiate: &Differentiate) -> Result<String, Diagnostic> {
+	let Differentiate { expression, variable, point } = differentiate;
+	let derivative = derivative::derivative(expression, variable, context)?;
+	let derivative = simplify::simplify(&derivative, context);
+	Ok(match point {
+		None => notation::formatted(&derivative.node),
+		Some(point) => {
+			let point = point.number(context)?;
+			context.bind(variable, point, || derivative.evaluate(context))?.to_string()
+		}
+	})
+}
+
+/// Finds the roots of an equation and formats them such as `x = 2, x = -2`.
+fn solve(context: &Context, solve: &Solve) -> Result<String, Diagnostic> {
+	let left = bytecode::function(&solve.left, &solve.variable, context);
+	let right = solve.right.as_ref().map(|right| bytecode::function(right, &solve.variable, context));
+	let function = &mut |value: f64| {
+		let left = left(value)?;
+		let right = right.as_ref().map(|right| right(value)).transpose()?;
+		Ok::<_, Diagnostic>(left - right.unwrap_or(0.0))
+	};
+
+	let roots = match &solve.search {
+		Some(Search::Guess(guess)) => {
+			let guess = guess.number(context)?;
+			let root = root::newton(function, guess)?;
+			vec![root.ok_or_else(|| Diagnostic::new(Error::NoConvergence, solve.span))?]
+		}
+		Some(Search::Interval(lower_node, upper_node)) => {
+			let (lower, upper) = (lower_node.number(context)?, upper_node.number(context)?);
+			if lower >= upper {
+				let span = Span(lower_node.span.byte_start(), upper_node.span.byte_end());
+				return Err(Diagnostic::new(Error::InvalidRange, span));
+			}
+			root::roots(function, lower, upper)?
+		}
+		None => root::roots(function, SEARCH_INTERVAL.0, SEARCH_INTERVAL.1)?,
+	};
+
+	if roots.is_empty() {
+		return Err(Diagnostic::new(Error::NoRoot, solve.span));
+	}
+
+	Ok(roots.iter().map(|root| format!("{} = {}", solve.variable, root))
+		.collect::<Vec<_>>().join(", "))
+}
+
+/// Formats the prime factorization of a value such as `2^3 * 3 * 5`.
+/// Values with a magnitude of zero or one have no prime factors and
+/// are written as they are.
+fn factorization(value: i64) -> String {
+	let magnitude = value.unsigned_abs();
+	if magnitude <= 1 {
+		return value.to_string();
+	}
+
+	let mut factors: Vec<_> = integer::factorize(magnitude).into_iter()
+		.map(|(factor, multiplicity)| match multiplicity {
+			1 => factor.to_string(),
+			_ => format!("{}^{}", factor, multiplicity),
+		}).collect();
+
+	if value < 0 {
+		factors.insert(0, "-1".to_owned());
+	}
+	factors.join(" * ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn table(context: &Context, expression: &str) -> Result<String, Diagnostic> {
+		match crate::command::command(expression) {
+			Some(Ok(Command::Table(table))) => Ok(csv(&table, &rows(context, &table)?)),
+			_ => panic!(),
+		}
+	}
+
+	#[test]
+	fn test_factorization() {
+		assert_eq!(factorization(360), "2^3 * 3^2 * 5");
+		assert_eq!(factorization(-12), "-1 * 2^2 * 3");
+		assert_eq!(factorization(-7), "-1 * 7");
+		assert_eq!(factorization(1), "1");
+		assert_eq!(factorization(-1), "-1");
+		assert_eq!(factorization(0), "0");
+	}
+
+	#[test]
+	fn test_table() {
+		let context = &Context::default();
+		assert_eq!(table(context, "table x^2 + 1 for x in 0..1 step 0.5").unwrap(),
+			"x,x ^ 2 + 1\n0,1\n0.5,1.25\n1,2");
+		assert_eq!(table(context, "table 1 / x for x in -0.2..0.2 step 0.1").unwrap(),
+			"x,1 / x\n-0.2,-5\n-0.1,-10\n0,Division by zero (= 0)\n0.1,10\n0.2,5");
+		let error = |expression| table(context, expression).unwrap_err().error.node;
+		assert_eq!(error("table x for x in 1..0"), Error::InvalidRange);
+		assert_eq!(error("table x for x in 0..1 step -1"), Error::Domain);
+		assert_eq!(error("table x for x in 0..1e5 step 0.1"), Error::IterationLimit);
+	}
+}
@@ -0,0 +1,50 @@
+use std::io::{Read, stdin};
+
+use crate::notation;
+
+/// Formats every line of each file in place, or formats the
+/// standard input to the standard output if there are no files.
+/// Empty lines and commands are left unchanged and lines that
+/// cannot be parsed are reported and left unchanged.
+pub fn format(paths: &[String]) -> super::Result {
+	let mut failures = false;
+	if paths.is_empty() {
+		let mut string = String::new();
+		stdin().read_to_string(&mut string)?;
+		print!("{}", lines(&string, "<stdin>", &mut failures));
+	}
+
+	for path in paths {
+		let string = std::fs::read_to_string(path)?;
+		let formatted = lines(&string, path, &mut failures);
+		if formatted != string {
+			std::fs::write(path, formatted)?;
+		}
+	}
+
+	match failures {
+		false => Ok(()),
+		true => Err("Some lines could not be formatted".into()),
+	}
+}
+
+fn lines(string: &str, path: &str, failures: &mut bool) -> String {
+	let mut formatted = String::new();
+	for (index, line) in string.lines().enumerate() {
+		let blank = line.trim().is_empty();
+		match blank || crate::command::command(line).is_some() {
+			true => formatted.push_str(line.trim_end()),
+			false => match notation::format(line) {
+				Ok(line) => formatted.push_str(&line),
+				Err(error) => {
+					let column = error.span.byte_start() + 1;
+					eprintln!("{}:{}:{}: {}", path, index + 1, column, error.node);
+					formatted.push_str(line);
+					*failures = true;
+				}
+			},
+		}
+		formatted.push('\n');
+	}
+	formatted
+}
@@ -11,7 +11,7 @@ pub fn history_up(context: &mut Context) -> Result {
 	match context.history().map(ToOwned::to_owned) {
 		None => context.history_offset -= 1,
 		Some(history) => {
-			if context.expression.len() > 0 {
+			if !context.expression.is_empty() {
 				queue!(stdout(), Left(context.expression.len() as u16))?;
 			}
 
@@ -2,21 +2,25 @@ use std::io::{stdout, Write};
 
 use crossterm::*;
 
+use crate::command::Command;
 use crate::context::Context;
+use crate::error::Error;
+use crate::value::Value;
+use crate::span::Spanned;
 
+use super::plot::Style;
 use super::{render, Result};
 
 /// Spawns an interface with immediate expression verification.
-pub fn interface() -> Result {
+pub fn interface(context: &mut Context) -> Result {
 	let mut reader = crossterm::input().read_sync();
 	let _screen = RawScreen::into_raw_mode()?;
-	print!("{}", super::PROMPT.white().bold());
+	print!("{}", super::prompt(context).white().bold());
 	stdout().flush()?;
 
-	let context = &mut Context::default();
-	while let Some(event) = reader.next() {
-		match event {
-			InputEvent::Keyboard(event) => match event {
+	for event in reader.by_ref() {
+		if let InputEvent::Keyboard(event) = event {
+			match event {
 				KeyEvent::Enter => evaluate(context, true)?,
 				KeyEvent::Char(key_character) => character(context, key_character)?,
 				KeyEvent::Backspace => erase(context)?,
@@ -35,6 +39,13 @@ pub fn interface() -> Result {
 					queue!(stdout(), Clear(ClearType::UntilNewLine))?;
 				}
 				KeyEvent::Ctrl('w') => erase_group(context)?,
+				KeyEvent::Ctrl('t') => {
+					context.angle_unit = context.angle_unit.next();
+					let prompt = super::prompt(context).white().bold().to_string();
+					let (_, row) = crossterm::cursor().pos()?;
+					queue!(stdout(), SavePos, Goto(0, row), Output(prompt), ResetPos)?;
+					evaluate(context, false)?;
+				}
 				KeyEvent::Up => super::history::history_up(context)?,
 				KeyEvent::Down => super::history::history_down(context)?,
 				KeyEvent::Left if context.cursor_position >= 1 => {
@@ -50,7 +61,6 @@ pub fn interface() -> Result {
 				}
 				_ => (),
 			}
-			_ => (),
 		}
 		stdout().flush()?;
 	}
@@ -114,6 +124,10 @@ pub fn evaluate(context: &mut Context, store: bool) -> Result {
 	let difference = (context.expression.chars().count() - context.cursor_position) as u16;
 	queue!(stdout(), Right(difference), Clear(ClearType::UntilNewLine))?;
 
+	if let Some(command) = crate::command::command(&context.expression) {
+		return self::command(context, command, store);
+	}
+
 	let coalescence = match super::check::check(context)? {
 		None => return render::anchor_start(context.cursor_position),
 		Some(coalescence) => coalescence,
@@ -121,7 +135,7 @@ pub fn evaluate(context: &mut Context, store: bool) -> Result {
 
 	if store {
 		let expression = &mut context.expression;
-		let expression = std::mem::replace(expression, String::new());
+		let expression = std::mem::take(expression);
 		context.cursor_position = 0;
 		context.push_history(expression);
 	}
@@ -136,20 +150,86 @@ pub fn evaluate(context: &mut Context, store: bool) -> Result {
 			true => {
 				queue!(stdout(), Clear(ClearType::UntilNewLine))?;
 				render::line_break(true)?;
-				let index = context.push_value(evaluation);
-				render::value_index(index);
-				render::evaluation(evaluation, None);
+				if context.explain {
+					print!("{}", crate::explain::parenthesised(&node.node));
+					render::line_break(false)?;
+				}
+
+				let index = context.push_value(evaluation.clone());
+				let indent = render::value_index(index);
+				match &evaluation {
+					Value::Matrix(matrix) =>
+						render::grid(matrix, indent, || render::line_break(false))?,
+					_ => render::value(&evaluation, None, context.estimates),
+				}
 			}
 			false => {
 				print!(" {}= ", Colored::Fg(Color::Green));
-				render::evaluation(evaluation, Some(Color::Green));
+				render::value(&evaluation, Some(Color::Green), context.estimates);
 				return render::anchor_start(context.cursor_position);
 			}
 		}
 	}
 
 	render::line_break(false)?;
-	print!("{}", super::PROMPT.white().bold());
+	print!("{}", super::prompt(context).white().bold());
 	Ok(())
 }
 
+fn command(context: &mut Context, command: std::result::Result<Command, Spanned<Error>>,
+           store: bool) -> Result {
+	let command = match command {
+		Ok(command) => command,
+		Err(error) => {
+			render::line_error(&error.into())?;
+			return render::anchor_start(context.cursor_position);
+		}
+	};
+
+	if !store {
+		render::clear_buffer()?;
+		return render::anchor_start(context.cursor_position);
+	}
+
+	let expression = std::mem::take(&mut context.expression);
+	context.cursor_position = 0;
+	context.push_history(expression);
+
+	let output = match command {
+		Command::Plot(plot) => {
+			// The curves leave space for the legend, the axis, its labels and the prompt.
+			let (columns, rows) = crossterm::terminal().size()?;
+			let size = (columns as usize, rows.saturating_sub(5) as usize);
+			super::command::plot(context, &plot, Style::Braille, size).map(Some)
+		}
+		Command::Table(table) if !table.csv => match super::command::rows(context, &table) {
+			Err(error) => Err(error),
+			Ok(rows) => {
+				render::line_break(true)?;
+				let expression = crate::notation::formatted(&table.expression.node);
+				render::table((&table.variable, &expression), &rows, || render::line_break(false))?;
+				render::line_break(false)?;
+				print!("{}", super::prompt(context).white().bold());
+				return Ok(());
+			}
+		},
+		command => super::command::execute(context, command),
+	};
+
+	match output {
+		Err(error) => {
+			render::line_error(&error)?;
+			render::line_break(false)?;
+		}
+		Ok(output) => {
+			render::line_break(true)?;
+			for line in output.iter().flat_map(|output| output.lines()) {
+				print!("{}", line);
+				render::line_break(false)?;
+			}
+		}
+	}
+
+	print!("{}", super::prompt(context).white().bold());
+	Ok(())
+}
@@ -1,12 +1,32 @@
-pub use basic::{basic, evaluate, evaluate_direct};
+pub use basic::{basic, evaluate_direct};
+pub use format::format;
+#[cfg(test)]
+pub use basic::evaluate;
 pub use interface::interface;
 
+use crate::context::Context;
+use crate::item::AngleUnit;
+
 type Result = std::result::Result<(), Box<dyn std::error::Error>>;
 
 pub const PROMPT: &str = ">> ";
 
+/// The prompt indicates the default angle unit when it is not radians.
+/// Every prompt has the same width as `PROMPT`.
+pub fn prompt(context: &Context) -> &'static str {
+	match context.angle_unit {
+		AngleUnit::Radians => PROMPT,
+		AngleUnit::Degrees => "d> ",
+		AngleUnit::Gradians => "g> ",
+	}
+}
+
+#[allow(clippy::module_inception)]
 mod interface;
 mod history;
 mod render;
 mod check;
 mod basic;
+mod command;
+mod format;
+mod plot;
@@ -0,0 +1,222 @@
+use crossterm::Color;
+
+use crate::error::Diagnostic;
+use crate::numeric::{self, Rounding};
+
+/// The width of the labels of the vertical axis.
+const LABEL_WIDTH: usize = 10;
+
+/// The colours of the curves of successive functions.
+const COLOURS: [Color; 6] = [Color::Cyan, Color::Yellow, Color::Magenta,
+	Color::Green, Color::Red, Color::Blue];
+
+/// The characters that mark the curves of successive functions in text.
+const MARKERS: [char; 6] = ['*', '+', 'o', 'x', '#', '%'];
+
+/// The bit of each dot of a braille character by row and column.
+const BRAILLE: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Style {
+	/// Draws curves in colour with braille characters of two by four dots.
+	Braille,
+	/// Draws curves with a character of a single dot that marks each curve.
+	Text,
+}
+
+impl Style {
+	/// The number of dots in the width and height of a character.
+	fn dots(&self) -> (usize, usize) {
+		match self {
+			Style::Braille => (2, 4),
+			Style::Text => (1, 1),
+		}
+	}
+
+	/// The characters of the horizontal axis, the vertical
+	/// axis, their intersection and a labelled tick.
+	fn axes(&self) -> [char; 4] {
+		match self {
+			Style::Braille => ['─', '│', '┼', '┤'],
+			Style::Text => ['-', '|', '+', '+'],
+		}
+	}
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+struct Cell {
+	dots: u8,
+	curve: usize,
+}
+
+/// Plots functions over an interval in lines that are no wider than the
+/// width with the given number of rows for the curves. The vertical axis
+/// spans the values of every function. Points at which a function cannot
+/// be evaluated are left empty and a function that cannot be evaluated at
+/// any point is rejected with its first error.
+pub fn plot<F>(functions: &[(String, F)], (lower, upper): (f64, f64),
+               (width, rows): (usize, usize), style: Style) -> Result<Vec<String>, Diagnostic>
+	where F: Fn(f64) -> Result<f64, Diagnostic> {
+	let (dot_width, dot_height) = style.dots();
+	let (columns, rows) = (width.saturating_sub(LABEL_WIDTH + 2).max(2), rows.max(2));
+	let (dots_x, dots_y) = (columns * dot_width, rows * dot_height);
+	let point = |dot: usize| lower + (upper - lower) * dot as f64 / (dots_x - 1) as f64;
+
+	let mut curves = Vec::new();
+	for (_, function) in functions {
+		let mut error = None;
+		let curve: Vec<_> = (0..dots_x).map(|dot| match function(point(dot)) {
+			Ok(value) => Some(value).filter(|value| value.is_finite()),
+			Err(diagnostic) => {
+				error.get_or_insert(diagnostic);
+				None
+			}
+		}).collect();
+
+		match error {
+			Some(error) if curve.iter().all(Option::is_none) => return Err(error),
+			_ => curves.push(curve),
+		}
+	}
+
+	let (minimum, maximum) = curves.iter().flatten().flatten().fold((f64::INFINITY,
+		f64::NEG_INFINITY), |(minimum, maximum), value| (minimum.min(*value), maximum.max(*value)));
+	let (bottom, top) = match minimum < maximum {
+		true => (minimum, maximum),
+		false if minimum.is_finite() => (minimum - 1.0, minimum + 1.0),
+		false => (-1.0, 1.0),
+	};
+
+	let dot_y = |value: f64| ((top - value) / (top - bottom) * (dots_y - 1) as f64).round() as usize;
+	let mut cells = vec![vec![Cell::default(); columns]; rows];
+	for (index, curve) in curves.iter().enumerate() {
+		let mut previous = None;
+		for (x, value) in curve.iter().enumerate() {
+			let y = value.map(dot_y);
+			if let Some(y) = y {
+				// Steep curves are joined up to the dot of the previous point.
+				let (start, end) = match previous {
+					Some(previous) if previous < y => (previous + 1, y),
+					Some(previous) if previous > y => (y, previous - 1),
+					_ => (y, y),
+				};
+				for y in start..=end {
+					let cell = &mut cells[y / dot_height][x / dot_width];
+					cell.dots |= match style {
+						Style::Braille => BRAILLE[y % dot_height][x % dot_width],
+						Style::Text => 1,
+					};
+					cell.curve = index;
+				}
+			}
+			previous = y;
+		}
+	}
+
+	let axis_row = (bottom..=top).contains(&0.0).then(|| dot_y(0.0) / dot_height);
+	let axis_column = (lower..=upper).contains(&0.0).then(|| {
+		let dot = (-lower / (upper - lower) * (dots_x - 1) as f64).round() as usize;
+		dot / dot_width
+	});
+
+	let [horizontal, vertical, intersection, tick] = style.axes();
+	let mut lines = vec![legend(functions, style)];
+	for (row, cells) in cells.iter().enumerate() {
+		let label = match row {
+			0 => Some(top),
+			_ if row == rows - 1 => Some(bottom),
+			_ if Some(row) == axis_row => Some(0.0),
+			_ => None,
+		};
+
+		let mut line = match label {
+			Some(value) => format!("{:>width$} {}", self::label(value), tick, width = LABEL_WIDTH),
+			None => format!("{:>width$} {}", "", vertical, width = LABEL_WIDTH),
+		};
+
+		for (column, cell) in cells.iter().enumerate() {
+			let axis = (Some(row) == axis_row, Some(column) == axis_column);
+			match (cell.dots, style) {
+				(0, _) => line.push(match axis {
+					(true, true) => intersection,
+					(true, false) => horizontal,
+					(false, true) => vertical,
+					(false, false) => ' ',
+				}),
+				(dots, Style::Braille) => {
+					let character = char::from_u32(0x2800 + dots as u32).unwrap();
+					let colour = COLOURS[cell.curve % COLOURS.len()];
+					line.push_str(&crossterm::style(character).with(colour).to_string());
+				}
+				(_, Style::Text) => line.push(MARKERS[cell.curve % MARKERS.len()]),
+			}
+		}
+		lines.push(line.trim_end().to_owned());
+	}
+
+	let corner = match style {
+		Style::Braille => '└',
+		Style::Text => '+',
+	};
+
+	let indent = " ".repeat(LABEL_WIDTH + 2);
+	let bottom_axis: String = std::iter::repeat_n(horizontal, columns).collect();
+	lines.push(format!("{:>width$} {}{}", "", corner, bottom_axis, width = LABEL_WIDTH));
+	let (lower, upper) = (label(lower), label(upper));
+	let gap = columns.saturating_sub(lower.len() + upper.len()).max(1);
+	lines.push(format!("{}{}{}{}", indent, lower, " ".repeat(gap), upper));
+	Ok(lines)
+}
+
+/// Lists each function with the mark of its curve.
+fn legend<F>(functions: &[(String, F)], style: Style) -> String {
+	let entries: Vec<_> = functions.iter().enumerate().map(|(index, (name, _))| match style {
+		Style::Braille => format!("{} {}", crossterm::style('⣿')
+			.with(COLOURS[index % COLOURS.len()]), name),
+		Style::Text => format!("{} {}", MARKERS[index % MARKERS.len()], name),
+	}).collect();
+	format!("{}{}", " ".repeat(LABEL_WIDTH + 2), entries.join("  "))
+}
+
+/// Formats a value of an axis to three significant figures.
+fn label(value: f64) -> String {
+	numeric::plain(numeric::round_significant(value, 3, Rounding::default()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::error::Error;
+	use crate::span::Span;
+
+	type Function = fn(f64) -> Result<f64, Diagnostic>;
+
+	#[test]
+	fn test_plot() {
+		let functions: [(String, Function); 2] = [("x".to_owned(), Ok), ("-x".to_owned(), |x| Ok(-x))];
+		let lines = plot(&functions, (-2.0, 2.0), (LABEL_WIDTH + 7, 5), Style::Text).unwrap();
+		assert_eq!(lines, [
+			"            * x  + -x",
+			"         2 ++ | *",
+			"           | +|*",
+			"         0 +--+--",
+			"           | *|+",
+			"        -2 +* | +",
+			"           +-----",
+			"            -2  2",
+		]);
+	}
+
+	#[test]
+	fn test_errors() {
+		let root: [(String, Function); 1] = [("sqrt x".to_owned(), |x| match x >= 0.0 {
+			true => Ok(x.sqrt()),
+			false => Err(Diagnostic::new(Error::Domain, Span(0, 1))),
+		})];
+
+		let lines = plot(&root, (-4.0, 4.0), (LABEL_WIDTH + 11, 3), Style::Text).unwrap();
+		assert_eq!(lines[1..4], ["         2 +    |  **", "           |    |**", "         0 +----*----"]);
+		let error = plot(&root, (-4.0, -1.0), (LABEL_WIDTH + 11, 3), Style::Text).unwrap_err();
+		assert_eq!(error.error.node, Error::Domain);
+	}
+}
@@ -2,42 +2,163 @@ use std::io::{stdout, Write};
 
 use crossterm::*;
 
-use crate::error::Error;
-use crate::span::{Span, Spanned};
+use crate::complex::Complex;
+use crate::error::Diagnostic;
+use crate::matrix::Matrix;
+use crate::numeric::plain;
+use crate::value::Value;
 
 use super::Result;
+use super::command::Row;
 
-pub fn value_index(index: usize) {
-	print!("{}{:x}{} ", "[".white().bold(), index, "]".white().bold());
+/// Prints the index of a value. Returns the width of the index.
+pub fn value_index(index: usize) -> usize {
+	let string = format!("{:x}", index);
+	print!("{}{}{} ", "[".white().bold(), string, "]".white().bold());
+	string.len() + 3
 }
 
-pub fn evaluation(evaluation: f64, colour: Option<Color>) {
-	let colour = Colored::Fg(colour.unwrap_or(Color::Grey));
-	let exponentiation_range = 1e-3 < evaluation.abs() && evaluation.abs() < 1e9;
-	match exponentiation_range || !evaluation.is_normal() {
-		true => print!("{}{}", colour, evaluation),
-		false => {
-			let string = format!("{:e}", evaluation);
-			print!("{}{}{}{}{}", colour, &string[..string.find('e').unwrap()],
-				"e".white().bold(), colour, &string[string.find('e').unwrap() + 1..]);
+/// Prints a value with the error estimate of the value if
+/// the value has an estimate and estimates are displayed.
+pub fn value(value: &Value, colour: Option<Color>, estimates: bool) {
+	match value {
+		Value::Number(number) => evaluation(*number, None, colour),
+		Value::Estimate(number, error) =>
+			evaluation(*number, Some(*error).filter(|_| estimates), colour),
+		Value::List(list) => self::list(list, |number| evaluation(*number, None, colour)),
+		Value::ComplexList(list) => self::list(list, |complex| self::complex(complex, colour)),
+		Value::Matrix(matrix) => self::list(&(0..matrix.rows()).collect::<Vec<_>>(), |row|
+			self::list(matrix.row(*row), |number| evaluation(*number, None, colour))),
+	}
+}
+
+/// Prints a matrix as a grid with the elements of each column aligned.
+/// Every row after the first follows a line break and an indent.
+pub fn grid(matrix: &Matrix, indent: usize, mut line_break: impl FnMut() -> Result) -> Result {
+	let widths: Vec<_> = (0..matrix.columns()).map(|column| (0..matrix.rows())
+		.map(|row| plain(matrix.row(row)[column]).chars().count()).max().unwrap_or(0)).collect();
+	for row in 0..matrix.rows() {
+		if row > 0 {
+			line_break()?;
+			print!("{}", " ".repeat(indent));
+		}
+
+		print!("{}", "[".white().bold());
+		for (column, element) in matrix.row(row).iter().enumerate() {
+			let separator = if column > 0 { 2 } else { 0 };
+			let padding = separator + widths[column] - plain(*element).chars().count();
+			print!("{}", " ".repeat(padding));
+			number(*element, None);
+		}
+		print!("{}", "]".white().bold());
+	}
+	Ok(())
+}
+
+/// Prints the rows of a table with the inputs aligned in a column.
+/// Errors are printed in place of the outputs of their rows. Every
+/// row after the header follows a line break.
+pub fn table(header: (&str, &str), rows: &[Row],
+             mut line_break: impl FnMut() -> Result) -> Result {
+	let width = rows.iter().map(|(input, _)| plain(*input).chars().count())
+		.chain(std::iter::once(header.0.chars().count())).max().unwrap();
+	let separator = || print!(" {} ", "│".white().bold());
+	let (variable, expression) = header;
+	print!("{}", " ".repeat(width - variable.chars().count()));
+	print!("{}", style(variable.to_owned()).white().bold());
+	separator();
+	print!("{}", style(expression.to_owned()).white().bold());
+
+	for (input, output) in rows {
+		line_break()?;
+		print!("{}", " ".repeat(width - plain(*input).chars().count()));
+		evaluation(*input, None, None);
+		separator();
+		match output {
+			Ok(output) => evaluation(*output, None, None),
+			Err(error) => print!("{}", style(error.to_string()).red()),
 		}
 	}
+	Ok(())
+}
+
+fn list<T>(list: &[T], element: impl Fn(&T)) {
+	print!("{}", "[".white().bold());
+	for (index, value) in list.iter().enumerate() {
+		if index > 0 {
+			print!("{}", ", ".white().bold());
+		}
+		element(value);
+	}
+	print!("{}", "]".white().bold());
+}
+
+fn complex(complex: &Complex, colour: Option<Color>) {
+	let Complex { real, imaginary } = *complex;
+	if imaginary == 0.0 {
+		return number(real, colour);
+	} else if real != 0.0 {
+		number(real, colour);
+		let sign = if imaginary < 0.0 { " - " } else { " + " };
+		print!("{}", sign.white().bold());
+	} else if imaginary < 0.0 {
+		print!("{}", "-".white().bold());
+	}
+
+	number(imaginary.abs(), colour);
+	print!("{}", "i".white().bold());
+}
+
+pub fn evaluation(evaluation: f64, estimate: Option<f64>, colour: Option<Color>) {
+	number(evaluation, colour);
+	if let Some(estimate) = estimate {
+		print!(" {} ", "±".white().bold());
+		number(estimate, colour);
+	}
+}
+
+fn number(number: f64, colour: Option<Color>) {
+	let colour = Colored::Fg(colour.unwrap_or(Color::Grey));
+	let string = plain(number);
+	match string.find('e') {
+		None => print!("{}{}", colour, string),
+		Some(index) => print!("{}{}{}{}{}", colour, &string[..index],
+			"e".white().bold(), colour, &string[index + 1..]),
+	}
 	print!("{}", Colored::Fg(Color::Reset));
 }
 
-pub fn line_error(error: &Spanned<Error>) -> Result {
-	let Span(byte_start, byte_end) = error.span;
-	let specific = "^".repeat(byte_end - byte_start).to_owned();
+pub fn line_error(error: &Diagnostic) -> Result {
+	let (byte_start, underline) = self::underline(error);
 	clear_buffer()?;
 
-	let string = error.node.to_string();
+	let string = error.to_string();
 	queue!(stdout(), SavePos, Down(1), SetFg(Color::Red))?;
 	anchor_start(byte_start)?;
 
-	Ok(queue!(stdout(), Output(specific), Right(1),
+	Ok(queue!(stdout(), Output(underline), Right(1),
 		Output(string), SetFg(Color::Reset), ResetPos)?)
 }
 
+/// Marks the primary span of a diagnostic with carets and the
+/// secondary span with dashes. Returns the byte offset of the
+/// first mark along with the marks.
+pub fn underline(error: &Diagnostic) -> (usize, String) {
+	let mut spans = vec![(error.error.span, '^')];
+	if let Some(secondary) = &error.secondary {
+		spans.insert(0, (secondary.span, '-'));
+	}
+
+	let byte_start = spans.iter().map(|(span, _)| span.byte_start()).min().unwrap();
+	let byte_end = spans.iter().map(|(span, _)| span.byte_end()).max().unwrap();
+	let mut underline = vec![' '; byte_end - byte_start];
+	for (span, mark) in spans {
+		underline[span.byte_start() - byte_start..span.byte_end() - byte_start]
+			.iter_mut().for_each(|character| *character = mark);
+	}
+	(byte_start, underline.into_iter().collect())
+}
+
 pub fn line_break(clear: bool) -> Result {
 	match clear {
 		true => clear_buffer(),
@@ -53,16 +174,25 @@ pub fn anchor_start(offset: usize) -> Result {
 	Ok(queue!(stdout(), Goto((super::PROMPT.len() + offset) as u16, row))?)
 }
 
+/// Clears every line below the cursor.
 pub fn clear_buffer() -> Result {
 	buffer_line()?;
-	Ok(queue!(stdout(), Down(1), Clear(ClearType::CurrentLine), Up(1))?)
+	Ok(queue!(stdout(), Down(1), Clear(ClearType::CurrentLine),
+		Clear(ClearType::FromCursorDown), Up(1))?)
 }
 
 pub fn buffer_line() -> Result {
+	buffer_lines(1)
+}
+
+/// Scrolls the terminal such that there are at least
+/// the given number of lines below the cursor.
+pub fn buffer_lines(count: u16) -> Result {
 	let (_, cursor_row) = crossterm::cursor().pos()?;
 	let (_, terminal_rows) = crossterm::terminal().size()?;
-	if cursor_row + 1 == terminal_rows {
-		queue!(stdout(), ScrollUp(1), Up(1))?;
+	let overflow = (cursor_row + count + 1).saturating_sub(terminal_rows);
+	if overflow > 0 {
+		queue!(stdout(), ScrollUp(overflow), Up(overflow))?;
 	}
 	Ok(())
 }
@@ -1,16 +1,167 @@
-#[derive(Debug, PartialEq)]
+use std::fmt;
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Function {
-	Trigonometric(Trigonometric, AngleUnit),
+	Trigonometric(Trigonometric, Option<AngleUnit>),
+	Hyperbolic(Hyperbolic),
+	Statistic(Statistic),
+	Series(Series, String),
+	Calculus(Calculus, String),
+	Roots,
+	Linear(Linear),
 	AbsoluteValue,
 	SquareRoot,
 	CubeRoot,
 	NaturalLogarithm,
 	BinaryLogarithm,
 	DecimalLogarithm,
+	Gamma,
+	Factorial,
+	Binomial,
+	Permutation,
+	Floor,
+	Ceiling,
+	Round,
+	RoundPlaces,
+	SignificantFigures,
+	Truncate,
+	Fraction,
+	Sign,
+	GreatestCommonDivisor,
+	LeastCommonMultiple,
+	IsPrime,
+	NextPrime,
+	PowerModulo,
+	InverseModulo,
+	/// Logical negation that is one for zero and zero otherwise.
+	Not,
 	UnaryMinus,
 }
 
-#[derive(Debug, PartialEq)]
+impl Function {
+	/// The number of terms the function takes as arguments.
+	pub fn arity(&self) -> usize {
+		match self {
+			Function::Binomial | Function::Permutation => 2,
+			Function::RoundPlaces | Function::SignificantFigures => 2,
+			Function::GreatestCommonDivisor | Function::LeastCommonMultiple => 2,
+			Function::InverseModulo | Function::Linear(Linear::Solve) => 2,
+			Function::Calculus(Calculus::Derivative, _) => 2,
+			Function::PowerModulo | Function::Series(_, _) => 3,
+			Function::Calculus(Calculus::Integral, _) => 3,
+			_ => 1,
+		}
+	}
+
+	/// The form of the function that takes an additional argument.
+	pub fn extend(&self) -> Option<Function> {
+		match self {
+			Function::Round => Some(Function::RoundPlaces),
+			_ => None,
+		}
+	}
+
+	/// The form of the function that binds a variable over its
+	/// parenthesised arguments such as `sum(k, 1, 10, k^2)`.
+	pub fn binding(&self, variable: String) -> Option<Function> {
+		match self {
+			Function::Statistic(Statistic::Sum) => Some(Function::Series(Series::Sum, variable)),
+			Function::Statistic(Statistic::Product) =>
+				Some(Function::Series(Series::Product, variable)),
+			Function::Calculus(calculus, _) => Some(Function::Calculus(*calculus, variable)),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Function {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Function::Trigonometric(function, unit) => {
+				use Trigonometric::*;
+				write!(f, "{}", match function {
+					Sine => "sin",
+					Cosine => "cos",
+					Tangent => "tan",
+					InverseSine => "asin",
+					InverseCosine => "acos",
+					InverseTangent => "atan",
+					Secant => "sec",
+					Cosecant => "csc",
+					Cotangent => "cot",
+					InverseSecant => "asec",
+					InverseCosecant => "acsc",
+					InverseCotangent => "acot",
+				})?;
+
+				match unit {
+					None => Ok(()),
+					Some(AngleUnit::Degrees) => write!(f, "'"),
+					Some(AngleUnit::Gradians) => write!(f, "'g"),
+					Some(AngleUnit::Radians) => write!(f, "'r"),
+				}
+			}
+			Function::Hyperbolic(function) => write!(f, "{}", match function {
+				Hyperbolic::Sine => "sinh",
+				Hyperbolic::Cosine => "cosh",
+				Hyperbolic::Tangent => "tanh",
+				Hyperbolic::InverseSine => "asinh",
+				Hyperbolic::InverseCosine => "acosh",
+				Hyperbolic::InverseTangent => "atanh",
+			}),
+			Function::Statistic(statistic) => write!(f, "{}", match statistic {
+				Statistic::Mean => "mean",
+				Statistic::Median => "median",
+				Statistic::StandardDeviation => "stdev",
+				Statistic::Variance => "var",
+				Statistic::Sum => "sum",
+				Statistic::Product => "prod",
+				Statistic::Minimum => "min",
+				Statistic::Maximum => "max",
+			}),
+			Function::Series(Series::Sum, _) => write!(f, "sum"),
+			Function::Series(Series::Product, _) => write!(f, "prod"),
+			Function::Calculus(Calculus::Integral, _) => write!(f, "integrate"),
+			Function::Calculus(Calculus::Derivative, _) => write!(f, "deriv"),
+			Function::Roots => write!(f, "roots"),
+			Function::Linear(linear) => write!(f, "{}", match linear {
+				Linear::Determinant => "det",
+				Linear::Inverse => "inv",
+				Linear::Transpose => "transpose",
+				Linear::Solve => "linsolve",
+			}),
+			Function::AbsoluteValue => write!(f, "abs"),
+			Function::SquareRoot => write!(f, "sqrt"),
+			Function::CubeRoot => write!(f, "cbrt"),
+			Function::NaturalLogarithm => write!(f, "ln"),
+			Function::BinaryLogarithm => write!(f, "log2"),
+			Function::DecimalLogarithm => write!(f, "log10"),
+			Function::Gamma => write!(f, "gamma"),
+			Function::Factorial => write!(f, "!"),
+			Function::Binomial => write!(f, "ncr"),
+			Function::Permutation => write!(f, "npr"),
+			Function::Floor => write!(f, "floor"),
+			Function::Ceiling => write!(f, "ceil"),
+			Function::Round | Function::RoundPlaces => write!(f, "round"),
+			Function::SignificantFigures => write!(f, "sigfig"),
+			Function::Truncate => write!(f, "trunc"),
+			Function::Fraction => write!(f, "frac"),
+			Function::Sign => write!(f, "sign"),
+			Function::GreatestCommonDivisor => write!(f, "gcd"),
+			Function::LeastCommonMultiple => write!(f, "lcm"),
+			Function::IsPrime => write!(f, "isprime"),
+			Function::NextPrime => write!(f, "nextprime"),
+			Function::PowerModulo => write!(f, "powmod"),
+			Function::InverseModulo => write!(f, "modinv"),
+			Function::Not => write!(f, "not"),
+			Function::UnaryMinus => write!(f, "-"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Trigonometric {
 	Sine,
 	Cosine,
@@ -18,31 +169,179 @@ pub enum Trigonometric {
 	InverseSine,
 	InverseCosine,
 	InverseTangent,
+	Secant,
+	Cosecant,
+	Cotangent,
+	InverseSecant,
+	InverseCosecant,
+	InverseCotangent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hyperbolic {
+	Sine,
+	Cosine,
+	Tangent,
+	InverseSine,
+	InverseCosine,
+	InverseTangent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statistic {
+	Mean,
+	Median,
+	StandardDeviation,
+	Variance,
+	Sum,
+	Product,
+	Minimum,
+	Maximum,
+}
+
+impl Statistic {
+	/// Summarizes a list of values. The standard deviation and
+	/// variance are of a sample and are undefined for a single value.
+	/// Every statistic is undefined for an empty list.
+	pub fn apply(&self, values: &[f64]) -> Result<f64, Error> {
+		if values.is_empty() {
+			return Err(Error::Domain);
+		}
+
+		let count = values.len() as f64;
+		let mean = values.iter().sum::<f64>() / count;
+		let variance = || values.iter().map(|value| (value - mean).powi(2))
+			.sum::<f64>() / (count - 1.0);
+		Ok(match self {
+			Statistic::Mean => mean,
+			Statistic::Median => {
+				let mut values = values.to_vec();
+				values.sort_unstable_by(f64::total_cmp);
+				let middle = values.len() / 2;
+				match values.len() % 2 {
+					0 => (values[middle - 1] + values[middle]) / 2.0,
+					_ => values[middle],
+				}
+			}
+			Statistic::StandardDeviation => variance().sqrt(),
+			Statistic::Variance => variance(),
+			Statistic::Sum => values.iter().sum(),
+			Statistic::Product => values.iter().product(),
+			Statistic::Minimum => values.iter().copied().fold(f64::INFINITY, f64::min),
+			Statistic::Maximum => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+		})
+	}
+}
+
+/// Functions of matrices from linear algebra.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Linear {
+	Determinant,
+	Inverse,
+	Transpose,
+	/// Solves a linear system from its matrix of coefficients
+	/// and the vector of its constants.
+	Solve,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Calculus {
+	Integral,
+	Derivative,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Series {
+	Sum,
+	Product,
+}
+
+impl Series {
+	/// The value of the series over an empty range.
+	pub fn identity(&self) -> f64 {
+		match self {
+			Series::Sum => 0.0,
+			Series::Product => 1.0,
+		}
+	}
+
+	pub fn apply(&self, accumulator: f64, term: f64) -> f64 {
+		match self {
+			Series::Sum => accumulator + term,
+			Series::Product => accumulator * term,
+		}
+	}
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
 pub enum AngleUnit {
+	#[default]
 	Radians,
 	Degrees,
+	Gradians,
 }
 
 impl AngleUnit {
+	/// Parses the unit suffix that follows the apostrophe
+	/// of a trigonometric function.
+	pub fn suffix(suffix: &str) -> Option<AngleUnit> {
+		match suffix {
+			"" | "d" => Some(AngleUnit::Degrees),
+			"g" => Some(AngleUnit::Gradians),
+			"r" => Some(AngleUnit::Radians),
+			_ => None,
+		}
+	}
+
+	pub fn parse(string: &str) -> Option<AngleUnit> {
+		match string {
+			"radians" | "rad" => Some(AngleUnit::Radians),
+			"degrees" | "deg" => Some(AngleUnit::Degrees),
+			"gradians" | "grad" => Some(AngleUnit::Gradians),
+			_ => None,
+		}
+	}
+
+	/// The unit that follows in the cycle of units.
+	pub fn next(&self) -> AngleUnit {
+		match self {
+			AngleUnit::Radians => AngleUnit::Degrees,
+			AngleUnit::Degrees => AngleUnit::Gradians,
+			AngleUnit::Gradians => AngleUnit::Radians,
+		}
+	}
+
 	pub fn apply(&self, radians: f64) -> f64 {
 		match self {
 			AngleUnit::Radians => radians,
 			AngleUnit::Degrees => radians.to_degrees(),
+			AngleUnit::Gradians => radians * 200.0 / std::f64::consts::PI,
 		}
 	}
 
+	/// Whether an angle is an exact multiple of a half turn once it is
+	/// offset by a number of quarter turns. Poles are found in the unit
+	/// of the angle as the conversion to radians is inexact, so angles
+	/// in radians are never found to be a multiple.
+	pub fn pole(&self, value: f64, quarters: f64) -> bool {
+		let quarter = match self {
+			AngleUnit::Radians => return false,
+			AngleUnit::Degrees => 90.0,
+			AngleUnit::Gradians => 100.0,
+		};
+		(value - quarters * quarter) % (2.0 * quarter) == 0.0
+	}
+
 	pub fn radians(&self, value: f64) -> f64 {
 		match self {
 			AngleUnit::Radians => value,
 			AngleUnit::Degrees => value.to_radians(),
+			AngleUnit::Gradians => value * std::f64::consts::PI / 200.0,
 		}
 	}
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Constant {
 	E,
 	Pi,
@@ -57,3 +356,12 @@ impl Constant {
 		}
 	}
 }
+
+impl fmt::Display for Constant {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Constant::E => write!(f, "e"),
+			Constant::Pi => write!(f, "pi"),
+		}
+	}
+}
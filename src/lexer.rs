@@ -2,7 +2,8 @@ use std::iter::Peekable;
 use std::str::CharIndices;
 
 use crate::error::Error;
-use crate::item::{AngleUnit, Constant, Function, Trigonometric};
+use crate::item::{AngleUnit, Calculus, Constant, Function, Hyperbolic, Linear, Statistic,
+	Trigonometric};
 use crate::span::{Span, Spanned};
 use crate::token::{Operator, Token};
 
@@ -10,20 +11,26 @@ use crate::token::{Operator, Token};
 pub struct Lexer<'a> {
 	string: &'a str,
 	characters: Peekable<CharIndices<'a>>,
-	last_coalesce: bool,
 	byte_end: usize,
 }
 
 impl<'a> Lexer<'a> {
-	pub fn new(string: &'a str) -> Lexer {
+	pub fn new(string: &'a str) -> Lexer<'a> {
 		Lexer {
 			string,
 			characters: string.char_indices().peekable(),
-			last_coalesce: false,
 			byte_end: string.len(),
 		}
 	}
 
+	/// Creates a lexer that starts at a byte offset into the string
+	/// so that the spans of tokens are relative to the whole string.
+	pub fn offset(string: &'a str, byte_start: usize) -> Lexer<'a> {
+		let mut lexer = Lexer::new(string);
+		while lexer.characters.next_if(|(index, _)| *index < byte_start).is_some() {}
+		lexer
+	}
+
 	fn skip_whitespace(&mut self) {
 		while let Some((_, character)) = self.characters.peek() {
 			match character.is_whitespace() {
@@ -44,7 +51,7 @@ impl<'a> Lexer<'a> {
 					continue;
 				}
 				'-' if exponent_divider => (),
-				_ if character.is_digit(16) => (),
+				_ if character.is_ascii_hexdigit() => (),
 				_ => return *index,
 			};
 
@@ -87,16 +94,31 @@ impl<'a> Lexer<'a> {
 		}.map(|terminal| Spanned::new(Token::Terminal(terminal), span))
 	}
 
-	fn take_coalesce(&mut self) -> (usize, usize) {
+	/// Takes a sequence of semicolons or a single semicolon followed by
+	/// the number of terms to coalesce such as `;3`. The token holds
+	/// one less than the number of terms.
+	fn take_coalesce(&mut self, byte_start: usize) -> Result<Spanned<Token>, Spanned<Error>> {
 		let mut counter = 1;
-		while let Some((index, character)) = self.characters.peek() {
-			match character == &';' {
-				true => self.characters.next(),
-				false => return (*index, counter),
-			};
+		while self.characters.next_if(|(_, character)| *character == ';').is_some() {
 			counter += 1;
 		}
-		(self.byte_end, counter)
+
+		let mut digits = String::new();
+		while let Some((_, digit)) = self.characters.next_if(|(_, character)|
+			counter == 1 && character.is_ascii_digit()) {
+			digits.push(digit);
+		}
+
+		let byte_end = self.characters.peek().map(|(index, _)| *index).unwrap_or(self.byte_end);
+		let span = Span(byte_start, byte_end);
+		let counter = match digits.is_empty() {
+			true => counter,
+			false => match digits.parse::<usize>() {
+				Ok(terms) if terms >= 2 => terms - 1,
+				_ => return Err(Spanned::new(Error::InvalidCoalesce, span)),
+			},
+		};
+		Ok(Spanned::new(Token::Coalesce(counter), span))
 	}
 
 	fn take_identifier(&mut self) -> usize {
@@ -122,22 +144,30 @@ impl<'a> Iterator for Lexer<'a> {
 			return self.next();
 		}
 
-		if character.is_digit(10) {
+		if character.is_ascii_digit() {
 			return Some(self.parse_number(character, byte_start));
 		} else if character == ';' {
-			let (byte_end, counter) = self.take_coalesce();
-			return Some(Ok(Spanned::new(Token::Coalesce(counter),
-				Span(byte_start, byte_end))));
+			return Some(self.take_coalesce(byte_start));
 		} else if character == '$' {
 			let byte_end = self.take_identifier();
 			let token = Token::Variable(self.string[byte_start + 1..byte_end].to_owned());
 			return Some(Ok(Spanned::new(token, Span(byte_start, byte_end))));
+		} else if character == '.' && self.characters.next_if(|(_, next)| *next == '.').is_some() {
+			return Some(Ok(Spanned::new(Token::Range, Span(byte_start, byte_start + 2))));
+		} else if let Some(operator) = Operator::symbol(&self.string[byte_start..]) {
+			let byte_end = byte_start + operator.definition().symbol.len();
+			while self.characters.next_if(|(index, _)| *index < byte_end).is_some() {}
+			return Some(Ok(Spanned::new(Token::Operator(operator), Span(byte_start, byte_end))));
 		}
 
 		if !character.is_ascii_punctuation() {
 			let byte_end = self.take_identifier();
 			let span = Span(byte_start, byte_end);
 			let mut slice = &self.string[byte_start..byte_end];
+			if let Some(operator) = Operator::word(slice) {
+				return Some(Ok(Spanned::new(Token::Operator(operator), span)));
+			}
+
 			let token = Spanned::new(match slice {
 				"abs" => Token::Function(Function::AbsoluteValue),
 				"sqrt" => Token::Function(Function::SquareRoot),
@@ -145,12 +175,57 @@ impl<'a> Iterator for Lexer<'a> {
 				"ln" => Token::Function(Function::NaturalLogarithm),
 				"log2" => Token::Function(Function::BinaryLogarithm),
 				"log10" => Token::Function(Function::DecimalLogarithm),
+				"gamma" => Token::Function(Function::Gamma),
+				"ncr" | "nCr" => Token::Function(Function::Binomial),
+				"npr" | "nPr" => Token::Function(Function::Permutation),
+				"floor" => Token::Function(Function::Floor),
+				"ceil" => Token::Function(Function::Ceiling),
+				"round" => Token::Function(Function::Round),
+				"sigfig" => Token::Function(Function::SignificantFigures),
+				"trunc" => Token::Function(Function::Truncate),
+				"frac" => Token::Function(Function::Fraction),
+				"sign" => Token::Function(Function::Sign),
+				"gcd" => Token::Function(Function::GreatestCommonDivisor),
+				"lcm" => Token::Function(Function::LeastCommonMultiple),
+				"isprime" => Token::Function(Function::IsPrime),
+				"nextprime" => Token::Function(Function::NextPrime),
+				"powmod" => Token::Function(Function::PowerModulo),
+				"modinv" => Token::Function(Function::InverseModulo),
+				"sinh" => Token::Function(Function::Hyperbolic(Hyperbolic::Sine)),
+				"cosh" => Token::Function(Function::Hyperbolic(Hyperbolic::Cosine)),
+				"tanh" => Token::Function(Function::Hyperbolic(Hyperbolic::Tangent)),
+				"asinh" => Token::Function(Function::Hyperbolic(Hyperbolic::InverseSine)),
+				"acosh" => Token::Function(Function::Hyperbolic(Hyperbolic::InverseCosine)),
+				"atanh" => Token::Function(Function::Hyperbolic(Hyperbolic::InverseTangent)),
+				"mean" => Token::Function(Function::Statistic(Statistic::Mean)),
+				"median" => Token::Function(Function::Statistic(Statistic::Median)),
+				"stdev" => Token::Function(Function::Statistic(Statistic::StandardDeviation)),
+				"var" => Token::Function(Function::Statistic(Statistic::Variance)),
+				"sum" => Token::Function(Function::Statistic(Statistic::Sum)),
+				"prod" => Token::Function(Function::Statistic(Statistic::Product)),
+				"min" => Token::Function(Function::Statistic(Statistic::Minimum)),
+				"max" => Token::Function(Function::Statistic(Statistic::Maximum)),
+				"roots" => Token::Function(Function::Roots),
+				"det" => Token::Function(Function::Linear(Linear::Determinant)),
+				"inv" => Token::Function(Function::Linear(Linear::Inverse)),
+				"transpose" => Token::Function(Function::Linear(Linear::Transpose)),
+				"linsolve" => Token::Function(Function::Linear(Linear::Solve)),
+				"integrate" => Token::Function(Function::Calculus(Calculus::Integral, String::new())),
+				"deriv" => Token::Function(Function::Calculus(Calculus::Derivative, String::new())),
+				"not" => Token::Function(Function::Not),
+				"if" => Token::If,
+				"then" => Token::Then,
+				"else" => Token::Else,
 				"e" => Token::Constant(Constant::E),
 				"pi" => Token::Constant(Constant::Pi),
 				_ => {
-					let mut unit = AngleUnit::Radians;
-					if let Some((index, '\'')) = slice.char_indices().last() {
-						unit = AngleUnit::Degrees;
+					let mut unit = None;
+					if let Some(index) = slice.find('\'') {
+						let error = Spanned::new(Error::InvalidItem, span);
+						unit = match AngleUnit::suffix(&slice[index + 1..]) {
+							None => return Some(Err(error)),
+							Some(unit) => Some(unit),
+						};
 						slice = &slice[..index];
 					}
 
@@ -161,6 +236,16 @@ impl<'a> Iterator for Lexer<'a> {
 						"asin" => Trigonometric::InverseSine,
 						"acos" => Trigonometric::InverseCosine,
 						"atan" => Trigonometric::InverseTangent,
+						"sec" => Trigonometric::Secant,
+						"csc" => Trigonometric::Cosecant,
+						"cot" => Trigonometric::Cotangent,
+						"asec" => Trigonometric::InverseSecant,
+						"acsc" => Trigonometric::InverseCosecant,
+						"acot" => Trigonometric::InverseCotangent,
+						_ if unit.is_none() => {
+							let token = Token::Identifier(slice.to_owned());
+							return Some(Ok(Spanned::new(token, span)));
+						}
 						_ => return Some(Err(Spanned::new(Error::InvalidItem, span))),
 					}, unit))
 				}
@@ -173,12 +258,10 @@ impl<'a> Iterator for Lexer<'a> {
 		let token = Spanned::new(match character {
 			'(' => Token::ParenthesisOpen,
 			')' => Token::ParenthesisClose,
-			'+' => Token::Operator(Operator::Add),
-			'-' => Token::Operator(Operator::Minus),
-			'*' => Token::Operator(Operator::Multiply),
-			'/' => Token::Operator(Operator::Divide),
-			'%' => Token::Operator(Operator::Modulo),
-			'^' => Token::Operator(Operator::Power),
+			'[' => Token::BracketOpen,
+			']' => Token::BracketClose,
+			',' => Token::Comma,
+			'!' => Token::Factorial,
 			_ => return Some(Err(Spanned::new(Error::InvalidCharacter(character), span))),
 		}, span);
 		Some(Ok(token))
@@ -201,6 +284,16 @@ mod tests {
 			Token::Terminal(54.0), Token::Coalesce(1), Token::Coalesce(1)]);
 	}
 
+	#[test]
+	fn test_coalesce() {
+		let tokens = |string| Lexer::new(string).map(|token| token.map(|token| token.node))
+			.collect::<Result<Vec<_>, _>>();
+		assert_eq!(tokens(";;; ;3;2"), Ok(vec![Token::Coalesce(3), Token::Coalesce(2),
+			Token::Coalesce(1)]));
+		assert_eq!(tokens(";;3"), Ok(vec![Token::Coalesce(2), Token::Terminal(3.0)]));
+		assert_eq!(tokens("1;1").unwrap_err(), Spanned::new(Error::InvalidCoalesce, Span(1, 3)));
+	}
+
 	#[test]
 	fn test_numerical_format() {
 		let string = "10 + -10.0 0x0a 0b1010 0o12 + -1e1 + 1_023_568";
@@ -213,6 +306,17 @@ mod tests {
 			Token::Terminal(1023568.0)]);
 	}
 
+	#[test]
+	fn test_comparison() {
+		let string = "1 <= 2 != 3! > 4 and not 5";
+		let tokens: Result<Vec<_>, _> = Lexer::new(string)
+			.map(|token| token.map(|token| token.node)).collect();
+		assert_eq!(tokens.unwrap(), &[Token::Terminal(1.0), Token::Operator(Operator::LessEqual),
+			Token::Terminal(2.0), Token::Operator(Operator::NotEqual), Token::Terminal(3.0),
+			Token::Factorial, Token::Operator(Operator::Greater), Token::Terminal(4.0),
+			Token::Operator(Operator::And), Token::Function(Function::Not), Token::Terminal(5.0)]);
+	}
+
 	#[test]
 	fn test_identifier() {
 		let string = "$ $0 $$ $identifier";
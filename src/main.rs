@@ -5,21 +5,46 @@ mod token;
 mod coalesce;
 mod parse;
 mod node;
+mod bytecode;
 mod item;
 mod interface;
 mod coalescence;
 mod context;
+mod numeric;
+mod integer;
+mod calculus;
+mod root;
+mod complex;
+mod matrix;
+mod command;
+mod explain;
+mod notation;
+mod simplify;
+mod derivative;
+mod value;
 #[cfg(test)]
 mod tests;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-	let argument = std::env::args().nth(1);
-	match argument.as_ref().map(String::as_str) {
-		Some("-b") | Some("--basic") => interface::basic()?,
-		Some("-e") | Some("--evaluate") => interface::evaluate_direct()?,
-		_ => if let Err(_) = interface::interface() {
-			interface::basic()?;
-		},
+	let arguments: Vec<_> = std::env::args().skip(1).collect();
+	let flag = |short: &str, long: &str| arguments.iter()
+		.any(|argument| argument == short || argument == long);
+
+	if arguments.first().map(String::as_str) == Some("fmt") {
+		return interface::format(&arguments[1..]);
+	}
+
+	let context = &mut context::Context::default();
+	context.ieee = flag("-i", "--ieee");
+	context.estimates = flag("-s", "--estimates");
+	context.explain = flag("-x", "--explain");
+
+	if flag("-b", "--basic") {
+		interface::basic(context)?;
+	} else if flag("-e", "--evaluate") {
+		interface::evaluate_direct(context)?;
+	} else if interface::interface(context).is_err() {
+		interface::basic(context)?;
 	}
 	Ok(())
 }
@@ -0,0 +1,264 @@
+use std::fmt;
+
+use crate::error::Error;
+
+/// The largest magnitude of a pivot relative to the largest element
+/// of a matrix at which the matrix is considered singular.
+const SINGULAR: f64 = 1e-12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+	rows: usize,
+	columns: usize,
+	elements: Vec<f64>,
+}
+
+impl Matrix {
+	/// Creates a matrix from rows of equal length. Returns
+	/// `None` if there are no rows or the rows differ in length.
+	pub fn from_rows(rows: Vec<Vec<f64>>) -> Option<Matrix> {
+		let columns = rows.first()?.len();
+		match rows.iter().all(|row| row.len() == columns) {
+			true => Some(Matrix { rows: rows.len(), columns, elements: rows.concat() }),
+			false => None,
+		}
+	}
+
+	/// Creates a matrix with a single column.
+	pub fn column(elements: Vec<f64>) -> Matrix {
+		Matrix { rows: elements.len(), columns: 1, elements }
+	}
+
+	pub fn identity(size: usize) -> Matrix {
+		let mut elements = vec![0.0; size * size];
+		(0..size).for_each(|index| elements[index * size + index] = 1.0);
+		Matrix { rows: size, columns: size, elements }
+	}
+
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	pub fn columns(&self) -> usize {
+		self.columns
+	}
+
+	pub fn row(&self, row: usize) -> &[f64] {
+		&self.elements[row * self.columns..(row + 1) * self.columns]
+	}
+
+	pub fn elements(&self) -> &[f64] {
+		&self.elements
+	}
+
+	/// Describes the dimensions of the matrix.
+	pub fn shape(&self) -> String {
+		format!("{}×{} matrix", self.rows, self.columns)
+	}
+
+	pub fn is_square(&self) -> bool {
+		self.rows == self.columns
+	}
+
+	pub fn map(&self, function: impl Fn(f64) -> f64) -> Matrix {
+		let elements = self.elements.iter().map(|element| function(*element)).collect();
+		Matrix { elements, ..*self }
+	}
+
+	/// Combines the corresponding elements of two matrices.
+	/// Returns `None` if the dimensions of the matrices differ.
+	pub fn zip(&self, other: &Matrix, function: impl Fn(f64, f64) -> f64) -> Option<Matrix> {
+		if (self.rows, self.columns) != (other.rows, other.columns) {
+			return None;
+		}
+
+		let elements = self.elements.iter().zip(&other.elements)
+			.map(|(left, right)| function(*left, *right)).collect();
+		Some(Matrix { elements, ..*self })
+	}
+
+	pub fn transpose(&self) -> Matrix {
+		let elements = (0..self.columns).flat_map(|column| (0..self.rows)
+			.map(move |row| self.elements[row * self.columns + column])).collect();
+		Matrix { rows: self.columns, columns: self.rows, elements }
+	}
+
+	/// Multiplies two matrices. Returns `None` if the number of columns
+	/// of the left matrix differs from the number of rows of the right.
+	pub fn product(&self, other: &Matrix) -> Option<Matrix> {
+		if self.columns != other.rows {
+			return None;
+		}
+
+		let elements = (0..self.rows).flat_map(|row| (0..other.columns).map(move |column|
+			(0..self.columns).map(|index| self.elements[row * self.columns + index] *
+				other.elements[index * other.columns + column]).sum())).collect();
+		Some(Matrix { rows: self.rows, columns: other.columns, elements })
+	}
+
+	/// Raises a square matrix to an integer power. Negative
+	/// powers are powers of the inverse of the matrix.
+	pub fn power(&self, exponent: i64) -> Result<Matrix, Error> {
+		let mut base = match exponent < 0 {
+			true => self.inverse()?,
+			false if self.is_square() => self.clone(),
+			false => return Err(Error::NonSquare),
+		};
+
+		let mut exponent = exponent.unsigned_abs();
+		let mut result = Matrix::identity(self.rows);
+		while exponent > 0 {
+			if exponent & 1 == 1 {
+				result = result.product(&base).unwrap();
+			}
+
+			base = base.product(&base).unwrap();
+			exponent >>= 1;
+		}
+		Ok(result)
+	}
+
+	pub fn determinant(&self) -> Result<f64, Error> {
+		match self.decompose()? {
+			None => Ok(0.0),
+			Some(decomposition) => Ok((0..self.rows).map(|index| decomposition
+				.elements[index * self.rows + index]).product::<f64>() * decomposition.sign),
+		}
+	}
+
+	pub fn inverse(&self) -> Result<Matrix, Error> {
+		let decomposition = self.decompose()?.ok_or(Error::SingularMatrix)?;
+		let columns: Vec<_> = (0..self.rows).map(|column| {
+			let mut unit = vec![0.0; self.rows];
+			unit[column] = 1.0;
+			decomposition.solve(unit)
+		}).collect();
+		Ok(Matrix::from_rows(columns).unwrap().transpose())
+	}
+
+	/// Solves the linear system with the matrix as its coefficients.
+	pub fn solve(&self, constants: Vec<f64>) -> Result<Vec<f64>, Error> {
+		if self.is_square() && constants.len() != self.rows {
+			return Err(Error::DimensionMismatch);
+		}
+
+		let decomposition = self.decompose()?.ok_or(Error::SingularMatrix)?;
+		Ok(decomposition.solve(constants))
+	}
+
+	/// Decomposes a square matrix into lower and upper triangular
+	/// factors with partial pivoting. Returns `None` if the matrix
+	/// is singular.
+	fn decompose(&self) -> Result<Option<Decomposition>, Error> {
+		if !self.is_square() {
+			return Err(Error::NonSquare);
+		}
+
+		let size = self.rows;
+		let mut elements = self.elements.clone();
+		let mut permutation: Vec<_> = (0..size).collect();
+		let mut sign = 1.0;
+
+		let largest = elements.iter().fold(0.0, |largest: f64, element| largest.max(element.abs()));
+		for column in 0..size {
+			let pivot = (column..size).max_by(|left, right| elements[left * size + column].abs()
+				.total_cmp(&elements[right * size + column].abs())).unwrap();
+			if elements[pivot * size + column].abs() <= SINGULAR * largest {
+				return Ok(None);
+			}
+
+			if pivot != column {
+				(0..size).for_each(|index| elements.swap(pivot * size + index, column * size + index));
+				permutation.swap(pivot, column);
+				sign = -sign;
+			}
+
+			for row in column + 1..size {
+				let factor = elements[row * size + column] / elements[column * size + column];
+				elements[row * size + column] = factor;
+				for index in column + 1..size {
+					elements[row * size + index] -= factor * elements[column * size + index];
+				}
+			}
+		}
+		Ok(Some(Decomposition { elements, permutation, sign }))
+	}
+}
+
+/// The lower and upper triangular factors of a matrix in one
+/// matrix with the permutation of the rows of the matrix.
+struct Decomposition {
+	elements: Vec<f64>,
+	permutation: Vec<usize>,
+	sign: f64,
+}
+
+impl Decomposition {
+	fn solve(&self, constants: Vec<f64>) -> Vec<f64> {
+		let size = self.permutation.len();
+		let mut solution: Vec<_> = self.permutation.iter().map(|row| constants[*row]).collect();
+		for row in 0..size {
+			for column in 0..row {
+				solution[row] -= self.elements[row * size + column] * solution[column];
+			}
+		}
+
+		for row in (0..size).rev() {
+			for column in row + 1..size {
+				solution[row] -= self.elements[row * size + column] * solution[column];
+			}
+			solution[row] /= self.elements[row * size + row];
+		}
+		solution
+	}
+}
+
+impl fmt::Display for Matrix {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "[")?;
+		for row in 0..self.rows {
+			if row > 0 {
+				write!(f, ", ")?;
+			}
+
+			write!(f, "[")?;
+			for (index, element) in self.row(row).iter().enumerate() {
+				match index {
+					0 => write!(f, "{}", element),
+					_ => write!(f, ", {}", element),
+				}?;
+			}
+			write!(f, "]")?;
+		}
+		write!(f, "]")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_product() {
+		let left = Matrix::from_rows(vec![vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+		let right = Matrix::from_rows(vec![vec![5.0], vec![6.0]]).unwrap();
+		assert_eq!(left.product(&right), Some(Matrix::column(vec![17.0, 39.0])));
+		assert_eq!(right.product(&left), None);
+		assert_eq!(left.transpose().row(0), &[1.0, 3.0]);
+		assert_eq!(left.power(0), Ok(Matrix::identity(2)));
+		assert_eq!(left.power(2).unwrap().elements(), &[7.0, 10.0, 15.0, 22.0]);
+	}
+
+	#[test]
+	fn test_decomposition() {
+		let matrix = Matrix::from_rows(vec![vec![0.0, 2.0], vec![1.0, 1.0]]).unwrap();
+		assert_eq!(matrix.determinant(), Ok(-2.0));
+		assert_eq!(matrix.solve(vec![4.0, 3.0]), Ok(vec![1.0, 2.0]));
+		assert_eq!(matrix.inverse().unwrap().product(&matrix), Some(Matrix::identity(2)));
+
+		let singular = Matrix::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+		assert_eq!(singular.determinant(), Ok(0.0));
+		assert_eq!(singular.inverse(), Err(Error::SingularMatrix));
+		assert_eq!(Matrix::column(vec![1.0, 2.0]).determinant(), Err(Error::NonSquare));
+	}
+}
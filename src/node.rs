@@ -1,67 +1,560 @@
+use crate::bytecode;
+use crate::calculus;
 use crate::context::Context;
-use crate::error::Error;
-use crate::item::{Function, Trigonometric};
-use crate::span::Spanned;
-use crate::token::Operator;
+use crate::error::{Diagnostic, Error};
+use crate::integer;
+use crate::item::{Calculus, Function, Hyperbolic, Linear, Series, Trigonometric};
+use crate::matrix::Matrix;
+use crate::numeric;
+use crate::root;
+use crate::simplify;
+use crate::span::{Span, Spanned};
+use crate::token::{boolean, Operand, Operator};
+use crate::value::Value;
 
-#[derive(Debug)]
+/// The largest number of terms that a series can evaluate.
+const MAXIMUM_ITERATIONS: i64 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
 	Terminal(f64),
 	Variable(String),
-	Function(Function, Box<Spanned<Node>>),
+	Function(Spanned<Function>, Vec<Spanned<Node>>),
 	Operator(Spanned<Operator>, Box<Spanned<Node>>, Box<Spanned<Node>>),
+	List(Vec<Spanned<Node>>),
+	Range(Spanned<String>, Spanned<String>),
+	Identifier(String),
+	/// Evaluates the consequent if the condition is not zero
+	/// and otherwise evaluates the alternative.
+	Conditional(Box<Spanned<Node>>, Box<Spanned<Node>>, Box<Spanned<Node>>),
 }
 
 impl Spanned<Node> {
-	pub fn evaluate(&self, context: &Context) -> Result<f64, Spanned<Error>> {
+	pub fn evaluate(&self, context: &Context) -> Result<Value, Diagnostic> {
 		Ok(match &self.node {
-			Node::Terminal(terminal) => *terminal,
+			Node::Terminal(terminal) => Value::Number(*terminal),
 			Node::Variable(variable) => context.variable(variable)
-				.map_err(|error| Spanned::new(error, self.span))?,
-			Node::Function(function, node) => {
-				let value = node.evaluate(context)?;
-				match function {
-					Function::Trigonometric(function, unit) => {
-						use Trigonometric::*;
-						match function {
-							Sine => unit.radians(value).sin(),
-							Cosine => unit.radians(value).cos(),
-							Tangent => unit.radians(value).tan(),
-							InverseSine => unit.apply(value.asin()),
-							InverseCosine => unit.apply(value.acos()),
-							InverseTangent => unit.apply(value.atan()),
+				.map_err(|error| Diagnostic::new(error, self.span))?,
+			Node::Identifier(identifier) => Value::Number(context.binding(identifier)
+				.ok_or_else(|| Diagnostic::new(Error::InvalidItem, self.span))?),
+			Node::List(elements) => {
+				let values = elements.iter().map(|element| element.evaluate(context))
+					.collect::<Result<Vec<_>, _>>()?;
+				match values.first() {
+					Some(Value::List(_)) => Value::Matrix(matrix(elements, values)?),
+					_ => Value::List(elements.iter().zip(values).map(|(element, value)|
+						number(element.span, value)).collect::<Result<_, _>>()?),
+				}
+			}
+			Node::Range(start, end) => {
+				let index = |variable: &Spanned<String>| context.index(&variable.node)
+					.map_err(|error| Diagnostic::new(error, variable.span));
+				let (start, end) = (index(start)?, index(end)?);
+				if start > end {
+					return Err(Diagnostic::new(Error::InvalidRange, self.span));
+				}
+
+				Value::List(context.range(start, end)
+					.map_err(|error| Diagnostic::new(error, self.span))?)
+			}
+			Node::Function(function, arguments) => match &function.node {
+				Function::Statistic(statistic) => {
+					let list = arguments[0].list(context)?;
+					let evaluation = statistic.apply(&list);
+					let argument = (arguments[0].span, Value::List(list));
+					let evaluation = evaluation.map_err(|error| Diagnostic::new(error, function.span)
+						.label(argument.0, argument.1.to_string()))?;
+					Value::Number(finite(context, evaluation, function.span, argument)?)
+				}
+				Function::Series(series, variable) => Value::Number(Self::series(context,
+					function, series, variable, arguments)?),
+				Function::Calculus(calculus, variable) =>
+					Self::calculus(context, function, calculus, variable, arguments)?,
+				Function::Roots => {
+					let coefficients = arguments[0].list(context)?;
+					let leading = coefficients.split_last().map_or(&[][..], |(_, leading)| leading);
+					if leading.iter().all(|coefficient| *coefficient == 0.0) {
+						let list = Value::List(coefficients).to_string();
+						return Err(Diagnostic::new(Error::Domain, function.span)
+							.label(arguments[0].span, list));
+					}
+
+					let roots = root::polynomial(&coefficients)
+						.ok_or_else(|| Diagnostic::new(Error::NoConvergence, function.span))?;
+					match roots.iter().all(|root| root.imaginary == 0.0) {
+						true => Value::List(roots.iter().map(|root| root.real).collect()),
+						false => Value::ComplexList(roots),
+					}
+				}
+				Function::Linear(linear) => Self::linear(context, function, linear, arguments)?,
+				_ => {
+					let values = arguments.iter().map(|argument| argument.evaluate(context))
+						.collect::<Result<Vec<_>, _>>()?;
+					match (&function.node, values.as_slice()) {
+						(Function::UnaryMinus, [value @ (Value::List(_) | Value::Matrix(_))]) =>
+							map(value, |element| -element),
+						_ => {
+							let values = arguments.iter().zip(values)
+								.map(|(argument, value)| number(argument.span, value))
+								.collect::<Result<Vec<_>, _>>()?;
+							let spans: Vec<_> = arguments.iter().map(|argument| argument.span).collect();
+							Value::Number(self::function(context, function, &spans, &values)?)
 						}
 					}
-					Function::AbsoluteValue => value.abs(),
-					Function::SquareRoot => match value >= 0.0 {
-						false => return Err(Spanned::new(Error::NegativeRoot, node.span)),
-						true => value.sqrt(),
-					},
-					Function::CubeRoot => match value >= 0.0 {
-						false => return Err(Spanned::new(Error::NegativeRoot, node.span)),
-						true => value.cbrt(),
-					},
-					Function::NaturalLogarithm => value.ln(),
-					Function::BinaryLogarithm => value.log2(),
-					Function::DecimalLogarithm => value.log10(),
-					Function::UnaryMinus => -value,
 				}
-			}
+			},
+			Node::Conditional(condition, consequent, alternative) =>
+				match condition.number(context)? != 0.0 {
+					true => consequent.evaluate(context)?,
+					false => alternative.evaluate(context)?,
+				},
 			Node::Operator(operator, left_node, right_node) => {
 				let left = left_node.evaluate(context)?;
+				match (operator.node, &left) {
+					(Operator::And, Value::Number(left) | Value::Estimate(left, _)) if *left == 0.0 =>
+						return Ok(Value::Number(0.0)),
+					(Operator::Or, Value::Number(left) | Value::Estimate(left, _)) if *left != 0.0 =>
+						return Ok(Value::Number(1.0)),
+					_ => (),
+				}
+
 				let right = right_node.evaluate(context)?;
-				match operator.node {
-					Operator::Add => left + right,
-					Operator::Minus => left - right,
-					Operator::Multiply => left * right,
-					Operator::Divide => match right != 0.0 {
-						false => return Err(Spanned::new(Error::ZeroDivision, right_node.span)),
-						true => left / right
-					}
-					Operator::Modulo => left % right,
-					Operator::Power => left.powf(right),
+				match (left, right) {
+					(Value::Number(left) | Value::Estimate(left, _),
+						Value::Number(right) | Value::Estimate(right, _)) => Value::Number(self::operator(
+						context, operator, (left_node.span, left), (right_node.span, right))?),
+					(left, right) => Self::structured(context, operator,
+						(left_node.span, left), (right_node.span, right))?,
 				}
 			}
 		})
 	}
+
+	/// Evaluates a node that must result in a number.
+	pub fn number(&self, context: &Context) -> Result<f64, Diagnostic> {
+		number(self.span, self.evaluate(context)?)
+	}
+
+	/// Evaluates a node that must result in a list.
+	fn list(&self, context: &Context) -> Result<Vec<f64>, Diagnostic> {
+		match self.evaluate(context)? {
+			Value::List(list) => Ok(list),
+			value => Err(Diagnostic::new(Error::ExpectedList, self.span)
+				.label(self.span, value.to_string())),
+		}
+	}
+
+	/// Evaluates a series by binding the variable to each
+	/// integer between the bounds inclusively.
+	fn series(context: &Context, function: &Spanned<Function>, series: &Series,
+	          variable: &str, arguments: &[Spanned<Node>]) -> Result<f64, Diagnostic> {
+		let bound = |index: usize| {
+			let argument = &arguments[index];
+			exact(function.span, (argument.span, argument.number(context)?))
+		};
+
+		let (lower, upper) = (bound(0)?, bound(1)?);
+		let count = (upper - lower + 1).max(0);
+		if count > MAXIMUM_ITERATIONS {
+			let span = Span(arguments[0].span.byte_start(), arguments[1].span.byte_end());
+			return Err(Diagnostic::new(Error::IterationLimit, function.span)
+				.label(span, count.to_string()));
+		}
+
+		let body = &simplify::simplify(&arguments[2], context);
+		let evaluate = bytecode::function(body, variable, context);
+		let mut accumulator = series.identity();
+		for index in lower..=upper {
+			let term = evaluate(index as f64)?;
+			accumulator = series.apply(accumulator, term);
+		}
+		finite(context, accumulator, function.span, (arguments[2].span, accumulator))
+	}
+
+	/// Evaluates the integral or derivative of the first argument
+	/// with respect to the variable along with an error estimate.
+	fn calculus(context: &Context, function: &Spanned<Function>, calculus: &Calculus,
+	            variable: &str, arguments: &[Spanned<Node>]) -> Result<Value, Diagnostic> {
+		let body = &simplify::simplify(&arguments[0], context);
+		let evaluate = &mut bytecode::function(body, variable, context);
+		let (evaluation, error) = match calculus {
+			Calculus::Integral => {
+				let (lower, upper) = (arguments[1].number(context)?, arguments[2].number(context)?);
+				calculus::integrate(evaluate, lower, upper)?
+			}
+			Calculus::Derivative =>
+				calculus::differentiate(evaluate, arguments[1].number(context)?)?,
+		};
+
+		let argument = (arguments[0].span, evaluation);
+		let evaluation = finite(context, evaluation, function.span, argument)?;
+		Ok(Value::Estimate(evaluation, error))
+	}
+
+	/// Evaluates a function of matrices from linear algebra. The
+	/// transpose of a list is a matrix with the list as its column.
+	fn linear(context: &Context, function: &Spanned<Function>, linear: &Linear,
+	          arguments: &[Spanned<Node>]) -> Result<Value, Diagnostic> {
+		let matrix = match (linear, arguments[0].evaluate(context)?) {
+			(_, Value::Matrix(matrix)) => matrix,
+			(Linear::Transpose, Value::List(list)) => return Ok(Value::Matrix(Matrix::column(list))),
+			(_, value) => return Err(Diagnostic::new(Error::ExpectedMatrix, arguments[0].span)
+				.label(arguments[0].span, value.to_string())),
+		};
+
+		let reject = |error: Error| Diagnostic::new(error, function.span)
+			.label(arguments[0].span, matrix.shape());
+		let evaluation = match linear {
+			Linear::Transpose => Value::Matrix(matrix.transpose()),
+			Linear::Determinant => Value::Number(matrix.determinant().map_err(reject)?),
+			Linear::Inverse => Value::Matrix(matrix.inverse().map_err(reject)?),
+			Linear::Solve => {
+				let constants = arguments[1].list(context)?;
+				let shape = Value::List(constants.clone()).shape();
+				Value::List(matrix.solve(constants).map_err(|error| match error {
+					Error::DimensionMismatch => mismatch(function.span,
+						(arguments[0].span, matrix.shape()), (arguments[1].span, shape)),
+					error => reject(error),
+				})?)
+			}
+		};
+		finite_elements(context, evaluation, function.span)
+	}
+
+	/// Evaluates an operator with an operand that is a list or a matrix.
+	/// Lists and matrices are added and subtracted element-wise whereas
+	/// multiplication is the product of matrices.
+	fn structured(context: &Context, operator: &Spanned<Operator>, (left_span, left): (Span, Value),
+	              (right_span, right): (Span, Value)) -> Result<Value, Diagnostic> {
+		let mismatch = || mismatch(operator.span, (left_span, left.shape()),
+			(right_span, right.shape()));
+		let reject = |span: Span, value: &Value| Diagnostic::new(Error::ExpectedNumber, span)
+			.label(span, value.to_string());
+
+		let evaluation = match (operator.node, &left, &right) {
+			(_, Value::ComplexList(_), _) => return Err(reject(left_span, &left)),
+			(_, _, Value::ComplexList(_)) => return Err(reject(right_span, &right)),
+			(Operator::Add | Operator::Minus | Operator::ElementMultiply, _, _) => {
+				let function: fn(f64, f64) -> f64 = match operator.node {
+					Operator::Add => |left, right| left + right,
+					Operator::Minus => |left, right| left - right,
+					_ => |left, right| left * right,
+				};
+				elementwise(&left, &right, function).ok_or_else(mismatch)?
+			}
+			(Operator::Multiply, Value::Number(scalar) | Value::Estimate(scalar, _), value) |
+			(Operator::Multiply, value, Value::Number(scalar) | Value::Estimate(scalar, _)) =>
+				map(value, |element| element * scalar),
+			(Operator::Multiply, _, _) => product(&left, &right).ok_or_else(mismatch)?,
+			(Operator::Divide, value, Value::Number(divisor) | Value::Estimate(divisor, _)) => {
+				if *divisor == 0.0 && !context.ieee {
+					return Err(Diagnostic::new(Error::ZeroDivision, operator.span)
+						.label(right_span, divisor.to_string()));
+				}
+				map(value, |element| element / divisor)
+			}
+			(Operator::Power, Value::Matrix(matrix),
+				Value::Number(exponent) | Value::Estimate(exponent, _)) => {
+				let exponent = exact(operator.span, (right_span, *exponent))?;
+				Value::Matrix(matrix.power(exponent).map_err(|error|
+					Diagnostic::new(error, operator.span).label(left_span, matrix.shape()))?)
+			}
+			(_, _, Value::Number(_) | Value::Estimate(_, _)) => return Err(reject(left_span, &left)),
+			_ => return Err(reject(right_span, &right)),
+		};
+		finite_elements(context, evaluation, operator.span)
+	}
+}
+
+/// Evaluates a function of numbers with the spans of its arguments.
+pub fn function(context: &Context, function: &Spanned<Function>,
+                spans: &[Span], values: &[f64]) -> Result<f64, Diagnostic> {
+	let argument = |index: usize| (spans[index], values[index]);
+	let reject = |error: Error, (span, value): (Span, f64)|
+		Diagnostic::new(error, function.span).label(span, value.to_string());
+	let domain = |valid: bool, error: Error| match valid || context.ieee {
+		false => Err(reject(error, argument(0))),
+		true => Ok(()),
+	};
+
+	let exact = |index: usize| exact(function.span, argument(index));
+
+	let natural = |index: usize| match exact(index)? {
+		value if value >= 0 => Ok(value as u64),
+		_ => Err(reject(Error::Domain, argument(index))),
+	};
+
+	let modulus = |index: usize| match exact(index)? {
+		value if value > 0 => Ok(value),
+		_ => Err(reject(Error::Domain, argument(index))),
+	};
+
+	let value = values[0];
+	let evaluation = match &function.node {
+		Function::Statistic(_) | Function::Series(_, _) |
+		Function::Calculus(_, _) | Function::Roots |
+		Function::Linear(_) => unreachable!(),
+		Function::Trigonometric(function, unit) => {
+			use Trigonometric::*;
+			let unit = unit.unwrap_or(context.angle_unit);
+			let radians = unit.radians(value);
+			match function {
+				Sine => radians.sin(),
+				Cosine => radians.cos(),
+				Tangent => {
+					domain(!unit.pole(value, 1.0), Error::Domain)?;
+					radians.tan()
+				}
+				InverseSine => unit.apply(value.asin()),
+				InverseCosine => unit.apply(value.acos()),
+				InverseTangent => unit.apply(value.atan()),
+				Secant => {
+					domain(!unit.pole(value, 1.0), Error::Domain)?;
+					1.0 / radians.cos()
+				}
+				Cosecant => {
+					domain(radians.sin() != 0.0 && !unit.pole(value, 0.0), Error::Domain)?;
+					1.0 / radians.sin()
+				}
+				Cotangent => {
+					domain(radians.sin() != 0.0 && !unit.pole(value, 0.0), Error::Domain)?;
+					radians.cos() / radians.sin()
+				}
+				InverseSecant => {
+					domain(value.abs() >= 1.0, Error::Domain)?;
+					unit.apply(value.recip().acos())
+				}
+				InverseCosecant => {
+					domain(value.abs() >= 1.0, Error::Domain)?;
+					unit.apply(value.recip().asin())
+				}
+				InverseCotangent =>
+					unit.apply(std::f64::consts::FRAC_PI_2 - value.atan()),
+			}
+		}
+		Function::Hyperbolic(function) => match function {
+			Hyperbolic::Sine => value.sinh(),
+			Hyperbolic::Cosine => value.cosh(),
+			Hyperbolic::Tangent => value.tanh(),
+			Hyperbolic::InverseSine => value.asinh(),
+			Hyperbolic::InverseCosine => {
+				domain(value >= 1.0, Error::Domain)?;
+				value.acosh()
+			}
+			Hyperbolic::InverseTangent => {
+				domain(value.abs() < 1.0, Error::Domain)?;
+				value.atanh()
+			}
+		},
+		Function::AbsoluteValue => value.abs(),
+		Function::SquareRoot => {
+			domain(value >= 0.0, Error::NegativeRoot)?;
+			value.sqrt()
+		}
+		Function::CubeRoot => {
+			domain(value >= 0.0, Error::NegativeRoot)?;
+			value.cbrt()
+		}
+		Function::NaturalLogarithm => {
+			domain(value > 0.0, Error::Domain)?;
+			value.ln()
+		}
+		Function::BinaryLogarithm => {
+			domain(value > 0.0, Error::Domain)?;
+			value.log2()
+		}
+		Function::DecimalLogarithm => {
+			domain(value > 0.0, Error::Domain)?;
+			value.log10()
+		}
+		Function::Gamma => {
+			domain(!numeric::is_integer(value) || value > 0.0, Error::Domain)?;
+			numeric::gamma(value)
+		}
+		Function::Factorial => {
+			let valid = !numeric::is_integer(value) || value >= 0.0;
+			domain(valid, Error::NegativeFactorial)?;
+			numeric::factorial(value)
+		}
+		Function::Binomial =>
+			numeric::binomial(natural(0)? as f64, natural(1)? as f64),
+		Function::Permutation =>
+			numeric::permutation(natural(0)? as f64, natural(1)? as f64),
+		Function::Floor => value.floor(),
+		Function::Ceiling => value.ceil(),
+		Function::Round => numeric::round(value, 0, context.rounding),
+		Function::RoundPlaces =>
+			numeric::round(value, exact(1)?, context.rounding),
+		Function::SignificantFigures => match exact(1)? {
+			figures if figures > 0 =>
+				numeric::round_significant(value, figures, context.rounding),
+			_ => return Err(reject(Error::Domain, argument(1))),
+		},
+		Function::Truncate => value.trunc(),
+		Function::Fraction => value.fract(),
+		Function::Sign => match value {
+			_ if value > 0.0 => 1.0,
+			_ if value < 0.0 => -1.0,
+			_ => value,
+		},
+		Function::GreatestCommonDivisor => {
+			let (left, right) = (exact(0)?, exact(1)?);
+			integer::gcd(left.unsigned_abs(), right.unsigned_abs()) as f64
+		}
+		Function::LeastCommonMultiple => {
+			let (left, right) = (exact(0)?, exact(1)?);
+			integer::lcm(left.unsigned_abs(), right.unsigned_abs())
+				.ok_or_else(|| Diagnostic::new(Error::Overflow, function.span))? as f64
+		}
+		Function::IsPrime => match exact(0)? {
+			value if value >= 0 && integer::is_prime(value as u64) => 1.0,
+			_ => 0.0,
+		},
+		Function::NextPrime => integer::next_prime(exact(0)?.max(0) as u64) as f64,
+		Function::PowerModulo => {
+			let (base, exponent, modulus) = (exact(0)?, natural(1)?, modulus(2)?);
+			let base = base.rem_euclid(modulus) as u64;
+			integer::power_modulo(base, exponent, modulus as u64) as f64
+		}
+		Function::InverseModulo => {
+			let (value, modulus) = (exact(0)?, modulus(1)?);
+			integer::inverse_modulo(value, modulus)
+				.ok_or_else(|| reject(Error::NoInverse, argument(0)))? as f64
+		}
+		Function::Not => boolean(value == 0.0),
+		Function::UnaryMinus => -value,
+	};
+	finite(context, evaluation, function.span, argument(0))
+}
+
+/// Evaluates an operator on numbers with its implementation.
+pub fn operator(context: &Context, operator: &Spanned<Operator>, (left_span, left): (Span, f64),
+                (right_span, right): (Span, f64)) -> Result<f64, Diagnostic> {
+	let definition = operator.node.definition();
+	if let Some(operand) = (definition.zero_division)(left, right).filter(|_| !context.ieee) {
+		let (span, value) = match operand {
+			Operand::Left => (left_span, left),
+			Operand::Right => (right_span, right),
+		};
+		return Err(Diagnostic::new(Error::ZeroDivision, operator.span)
+			.label(span, value.to_string()));
+	}
+
+	let evaluation = (definition.apply)(left, right);
+	finite(context, evaluation, operator.span, (right_span, right))
+}
+
+/// Converts an argument of a function into an integer that is exact.
+fn exact(function: Span, (span, value): (Span, f64)) -> Result<i64, Diagnostic> {
+	let reject = |error| Err(Diagnostic::new(error, function).label(span, value.to_string()));
+	match value {
+		_ if value.abs() > integer::MAXIMUM_EXACT => reject(Error::InexactInteger),
+		_ if numeric::is_integer(value) => Ok(value as i64),
+		_ => reject(Error::NonInteger),
+	}
+}
+
+/// Rejects a result that is not a number or is infinite unless the
+/// context permits such results. Results that are not a number
+/// are labelled with the value of the offending argument.
+fn finite(context: &Context, evaluation: f64, span: Span,
+          (argument, value): (Span, impl ToString)) -> Result<f64, Diagnostic> {
+	match evaluation.is_finite() || context.ieee {
+		true => Ok(evaluation),
+		false if evaluation.is_nan() => Err(Diagnostic::new(Error::Domain, span)
+			.label(argument, value.to_string())),
+		false => Err(Diagnostic::new(Error::Overflow, span)),
+	}
+}
+
+/// Converts the value of a term into a number.
+fn number(span: Span, value: Value) -> Result<f64, Diagnostic> {
+	match value {
+		Value::Number(number) | Value::Estimate(number, _) => Ok(number),
+		value => Err(Diagnostic::new(Error::ExpectedNumber, span).label(span, value.to_string())),
+	}
+}
+
+/// Collects the lists that are the rows of a matrix literal. Rows
+/// that differ in length from the first row are rejected.
+fn matrix(elements: &[Spanned<Node>], values: Vec<Value>) -> Result<Matrix, Diagnostic> {
+	let first = (elements[0].span, values[0].shape());
+	let columns = match &values[0] {
+		Value::List(list) => list.len(),
+		_ => unreachable!(),
+	};
+
+	let rows = elements.iter().zip(values).map(|(element, value)| match value {
+		Value::List(row) if row.len() == columns => Ok(row),
+		Value::List(_) => Err(Diagnostic::new(Error::DimensionMismatch, element.span)
+			.label(first.0, first.1.clone())),
+		value => Err(Diagnostic::new(Error::ExpectedList, element.span)
+			.label(element.span, value.to_string())),
+	}).collect::<Result<Vec<_>, _>>()?;
+	Ok(Matrix::from_rows(rows).unwrap())
+}
+
+/// Applies a function to each element of a list or a matrix.
+fn map(value: &Value, function: impl Fn(f64) -> f64) -> Value {
+	match value {
+		Value::List(list) => Value::List(list.iter().map(|element| function(*element)).collect()),
+		Value::Matrix(matrix) => Value::Matrix(matrix.map(function)),
+		value => value.clone(),
+	}
+}
+
+/// Combines the corresponding elements of two lists or two matrices.
+/// Returns `None` if the dimensions of the operands differ.
+fn elementwise(left: &Value, right: &Value, function: fn(f64, f64) -> f64) -> Option<Value> {
+	match (left, right) {
+		(Value::List(left), Value::List(right)) if left.len() == right.len() =>
+			Some(Value::List(left.iter().zip(right)
+				.map(|(left, right)| function(*left, *right)).collect())),
+		(Value::Matrix(left), Value::Matrix(right)) => left.zip(right, function).map(Value::Matrix),
+		_ => None,
+	}
+}
+
+/// Multiplies lists and matrices. A list is a column on the right of a
+/// matrix and a row on the left of a matrix. The product of two lists
+/// is their dot product. Returns `None` if the dimensions do not match.
+fn product(left: &Value, right: &Value) -> Option<Value> {
+	let list = |matrix: Matrix| Value::List(matrix.elements().to_vec());
+	match (left, right) {
+		(Value::List(left), Value::List(right)) => (left.len() == right.len()).then(||
+			Value::Number(left.iter().zip(right).map(|(left, right)| left * right).sum())),
+		(Value::Matrix(left), Value::Matrix(right)) => left.product(right).map(Value::Matrix),
+		(Value::Matrix(left), Value::List(right)) =>
+			left.product(&Matrix::column(right.clone())).map(list),
+		(Value::List(left), Value::Matrix(right)) =>
+			Matrix::column(left.clone()).transpose().product(right).map(list),
+		_ => None,
+	}
+}
+
+/// Rejects operands of which the dimensions do not match
+/// and labels both operands with their dimensions.
+fn mismatch(span: Span, (left, left_shape): (Span, String),
+            (right, right_shape): (Span, String)) -> Diagnostic {
+	Diagnostic::new(Error::DimensionMismatch, span).label(Span(left.byte_start(),
+		right.byte_end()), format!("{} and {}", left_shape, right_shape))
+}
+
+/// Rejects a value with elements that are not a number or
+/// are infinite unless the context permits such elements.
+fn finite_elements(context: &Context, value: Value, span: Span) -> Result<Value, Diagnostic> {
+	let elements = match &value {
+		Value::Number(number) => std::slice::from_ref(number),
+		Value::List(list) => list.as_slice(),
+		Value::Matrix(matrix) => matrix.elements(),
+		_ => &[],
+	};
+
+	match elements.iter().copied().find(|element| !element.is_finite()) {
+		Some(element) if !context.ieee => Err(Diagnostic::new(match element.is_nan() {
+			true => Error::Domain,
+			false => Error::Overflow,
+		}, span)),
+		_ => Ok(value),
+	}
 }
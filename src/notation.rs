@@ -0,0 +1,289 @@
+use crate::coalesce;
+use crate::error::Error;
+use crate::explain;
+use crate::item::{Constant, Function};
+use crate::lexer::Lexer;
+use crate::node::Node;
+use crate::numeric;
+use crate::parse;
+use crate::span::Spanned;
+
+/// How a compound term that is an operand or an argument is delimited.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Form {
+	/// The term is written without delimiters.
+	Bare,
+	/// The term is followed by a coalesce of its terms.
+	Coalesce,
+	Parenthesis,
+}
+
+/// Writes a node with coalesces in place of parentheses wherever the
+/// coalesced expression parses into the same node. Each compound term
+/// is written without delimiters if possible and otherwise with a
+/// coalesce in preference to parentheses.
+pub fn coalesced(node: &Node) -> String {
+	delimit(node, &[Form::Bare, Form::Coalesce])
+}
+
+/// Writes a node in canonical form with the fewest parentheses that
+/// preserve the grouping of its operators and functions. Functions
+/// are written with their canonical names and binary operators are
+/// surrounded by spaces.
+pub fn formatted(node: &Node) -> String {
+	delimit(node, &[Form::Bare])
+}
+
+/// Parses an expression and writes it in canonical form.
+pub fn format(expression: &str) -> Result<String, Spanned<Error>> {
+	let lexer = &mut Lexer::new(expression);
+	let node = parse::parse_root(coalesce::coalesce_root(lexer)?);
+	Ok(formatted(&node.node))
+}
+
+/// Chooses the form of each compound term from the outermost term
+/// inwards. A term takes the first of the given forms with which the
+/// written expression parses into the same node and otherwise takes
+/// parentheses. The grouping of the node is therefore preserved by
+/// every written expression.
+fn delimit(node: &Node, candidates: &[Form]) -> String {
+	let mut writer = Writer::new(&[]);
+	writer.root(node);
+
+	let mut forms = vec![Form::Parenthesis; writer.group];
+	for index in 0..forms.len() {
+		for form in candidates {
+			forms[index] = *form;
+			let string = Writer::new(&forms).root(node);
+			if string.is_some_and(|string| round_trip(node, &string)) {
+				break;
+			}
+			forms[index] = Form::Parenthesis;
+		}
+	}
+	Writer::new(&forms).root(node).unwrap()
+}
+
+/// Whether an expression parses into a node. The identifiers of the
+/// node are bound so that the identifiers of a command such as the
+/// variable of a derivative are written as they are.
+fn round_trip(node: &Node, string: &str) -> bool {
+	let lexer = &mut Lexer::new(string);
+	let bound = &mut Vec::new();
+	identifiers(node, &[], bound);
+	match coalesce::coalesce_bound(lexer, bound) {
+		Ok(coalescence) => equivalent(node, &parse::parse_root(coalescence).node),
+		Err(_) => false,
+	}
+}
+
+/// Collects the identifiers of a node that are not bound within
+/// the node. Binding the others would prevent functions such as
+/// `integrate` from finding the variable that they bind.
+fn identifiers(node: &Node, bound: &[String], identifiers: &mut Vec<String>) {
+	let children: Vec<&Spanned<Node>> = match node {
+		Node::Identifier(identifier) => {
+			if !bound.contains(identifier) && !identifiers.contains(identifier) {
+				identifiers.push(identifier.clone());
+			}
+			return;
+		}
+		Node::Function(function, arguments) => {
+			if let Function::Series(_, variable) | Function::Calculus(_, variable) = &function.node {
+				let bound = &[bound, std::slice::from_ref(variable)].concat();
+				for argument in arguments {
+					self::identifiers(&argument.node, bound, identifiers);
+				}
+				return;
+			}
+			arguments.iter().collect()
+		}
+		Node::List(elements) => elements.iter().collect(),
+		Node::Operator(_, left, right) => vec![left, right],
+		Node::Conditional(condition, consequent, alternative) =>
+			vec![condition, consequent, alternative],
+		_ => return,
+	};
+
+	for child in children {
+		self::identifiers(&child.node, bound, identifiers);
+	}
+}
+
+/// Whether two nodes have the same structure regardless of their spans.
+fn equivalent(left: &Node, right: &Node) -> bool {
+	let all = |left: &[Spanned<Node>], right: &[Spanned<Node>]| left.len() == right.len() &&
+		left.iter().zip(right).all(|(left, right)| equivalent(&left.node, &right.node));
+	match (left, right) {
+		(Node::Terminal(left), Node::Terminal(right)) => left == right,
+		(Node::Variable(left), Node::Variable(right)) => left == right,
+		(Node::Identifier(left), Node::Identifier(right)) => left == right,
+		(Node::Range(left_start, left_end), Node::Range(right_start, right_end)) =>
+			left_start.node == right_start.node && left_end.node == right_end.node,
+		(Node::List(left), Node::List(right)) => all(left, right),
+		(Node::Function(left, left_arguments), Node::Function(right, right_arguments)) =>
+			left.node == right.node && all(left_arguments, right_arguments),
+		(Node::Operator(left, left_first, left_second),
+			Node::Operator(right, right_first, right_second)) => left.node == right.node &&
+			equivalent(&left_first.node, &right_first.node) &&
+			equivalent(&left_second.node, &right_second.node),
+		(Node::Conditional(left_condition, left_consequent, left_alternative),
+			Node::Conditional(right_condition, right_consequent, right_alternative)) =>
+			equivalent(&left_condition.node, &right_condition.node) &&
+				equivalent(&left_consequent.node, &right_consequent.node) &&
+				equivalent(&left_alternative.node, &right_alternative.node),
+		_ => false,
+	}
+}
+
+/// Writes a number as the constant with its value if there is one.
+fn terminal(value: f64) -> String {
+	[Constant::E, Constant::Pi].iter().find(|constant| constant.value() == value)
+		.map(Constant::to_string).unwrap_or_else(|| numeric::plain(value))
+}
+
+/// Writes a node with the form of each compound term in the
+/// order that the compound terms appear in the expression.
+struct Writer<'a> {
+	forms: &'a [Form],
+	/// The number of compound terms written so far.
+	group: usize,
+}
+
+impl<'a> Writer<'a> {
+	fn new(forms: &'a [Form]) -> Self {
+		Writer { forms, group: 0 }
+	}
+
+	/// Writes a node that is a whole expression, an element of a list or
+	/// an argument of a call. Returns `None` if a term has the coalesce
+	/// form but is too short to be coalesced.
+	fn root(&mut self, node: &Node) -> Option<String> {
+		self.node(node).map(|(string, _)| string)
+	}
+
+	/// Writes a node with the number of terms that the node
+	/// contributes to the sequence that encloses it.
+	fn node(&mut self, node: &Node) -> Option<(String, usize)> {
+		Some(match node {
+			Node::Terminal(terminal) => (self::terminal(*terminal), 1),
+			Node::Variable(variable) => (format!("${}", variable), 1),
+			Node::Identifier(identifier) => (identifier.clone(), 1),
+			Node::Range(start, end) => (format!("${}..${}", start.node, end.node), 1),
+			Node::List(elements) => (format!("[{}]", self.list(elements)?), 1),
+			Node::Conditional(condition, consequent, alternative) =>
+				(format!("if {} then {} else {}", self.root(&condition.node)?,
+					self.root(&consequent.node)?, self.root(&alternative.node)?), 1),
+			Node::Operator(operator, left, right) => {
+				let (left, left_terms) = self.term(&left.node)?;
+				let (right, right_terms) = self.term(&right.node)?;
+				let symbol = operator.node.definition().symbol;
+				(format!("{} {} {}", left, symbol, right), left_terms + right_terms)
+			}
+			Node::Function(function, arguments) => match &function.node {
+				Function::Series(_, variable) => (format!("{}({}, {})", function.node,
+					variable, self.list(arguments)?), 1),
+				Function::Calculus(_, variable) if !variable.is_empty() =>
+					(format!("{}({})", function.node, self.list(arguments)?), 1),
+				Function::Factorial => {
+					let (argument, terms) = self.term(&arguments[0].node)?;
+					(format!("{}!", argument), terms)
+				}
+				Function::UnaryMinus => {
+					let (argument, terms) = self.term(&arguments[0].node)?;
+					(format!("-{}", argument), terms + 1)
+				}
+				_ => {
+					// The function with every argument except its last is a single term.
+					let (mut string, mut terms) = (function.node.to_string(), 1);
+					for argument in arguments {
+						let (argument, argument_terms) = self.term(&argument.node)?;
+						string = string + " " + &argument;
+						terms = 1 + argument_terms;
+					}
+					(string, terms)
+				}
+			},
+		})
+	}
+
+	/// Writes a node that is an operand or an argument.
+	fn term(&mut self, node: &Node) -> Option<(String, usize)> {
+		if !explain::compound(node) {
+			return self.node(node);
+		}
+
+		let form = self.forms.get(self.group).copied().unwrap_or(Form::Parenthesis);
+		self.group += 1;
+		let (string, terms) = self.node(node)?;
+		match form {
+			Form::Bare => Some((string, terms)),
+			Form::Parenthesis => Some((format!("({})", string), 1)),
+			Form::Coalesce if terms > 1 => {
+				// Adjacent coalesces are separated so that they are not
+				// read as a single coalesce of more terms.
+				let separator = if string.ends_with(';') { " " } else { "" };
+				Some((string + separator + &";".repeat(terms - 1), 1))
+			}
+			Form::Coalesce => None,
+		}
+	}
+
+	/// Writes the elements of a list or the arguments of a call.
+	fn list(&mut self, nodes: &[Spanned<Node>]) -> Option<String> {
+		let strings = nodes.iter().map(|node| self.root(&node.node))
+			.collect::<Option<Vec<_>>>()?;
+		Some(strings.join(", "))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn node(expression: &str) -> Node {
+		crate::tests::parse(expression, &[]).node
+	}
+
+	#[test]
+	fn test_coalesced() {
+		let coalesced = |expression| super::coalesced(&node(expression));
+		assert_eq!(coalesced("10 - (2 + 4)"), "10 - 2 + 4;");
+		assert_eq!(coalesced("1 / (1 + 2 + 3)"), "1 / 1 + 2 + 3;;");
+		assert_eq!(coalesced("1 / (2 - (3 + 4))"), "1 / 2 - 3 + 4; ;");
+		assert_eq!(coalesced("(10) - 2 + 4"), "10 - 2 + 4");
+		assert_eq!(coalesced("(-2) ^ 2 * (2 + 3)!"), "-2; ^ 2 * 2 + 3;!");
+		assert_eq!(coalesced("ncr (round 2.5) 2"), "ncr round 2.5 2");
+		assert_eq!(coalesced("sqrt (4 + 5)"), "sqrt 4 + 5;");
+		assert_eq!(coalesced("2 * (ncr 5 2 + 1)"), "2 * ncr 5 2 + 1;;");
+		assert_eq!(coalesced("sum(k, 1, 10, (k + 1) * 2)"), "sum(k, 1, 10, k + 1; * 2)");
+		assert_eq!(coalesced("(if 1 then 2 else 3) + 1"), "(if 1 then 2 else 3) + 1");
+	}
+
+	#[test]
+	fn test_formatted() {
+		let format = |expression| super::format(expression).unwrap();
+		assert_eq!(format("(10)-2+4;"), "10 - (2 + 4)");
+		assert_eq!(format("1 / 2 - 3 + 4; ;"), "1 / (2 - (3 + 4))");
+		assert_eq!(format("2^(3^2) * (2^3)^2"), "2 ^ 3 ^ 2 * (2 ^ 3) ^ 2");
+		assert_eq!(format("(-2)^2 + -(2^2) - (-2)"), "(-2) ^ 2 + -2 ^ 2 - -2");
+		assert_eq!(format("sin'd (0x5a) * pi"), "sin' 90 * pi");
+		assert_eq!(format("(sqrt 4)! + sqrt (4!)"), "(sqrt 4)! + sqrt 4!");
+		assert_eq!(format("[1,(2)]+sum(k,1,10,(k^2))"), "[1, 2] + sum(k, 1, 10, k ^ 2)");
+		assert_eq!(format("(1 < 2) and (if 1 then 2 else 3)"), "1 < 2 and if 1 then 2 else 3");
+		assert_eq!(format("(if 1 then 2 else 3) + 1"), "(if 1 then 2 else 3) + 1");
+		assert_eq!(format("1e-300 + 0.0000001 * 2000000000"), "1e-300 + 1e-7 * 2e9");
+	}
+
+	#[test]
+	fn test_formatted_evaluation() {
+		let context = &crate::context::Context::default();
+		let evaluate = |expression: &str| crate::tests::parse(expression, &[])
+			.evaluate(context).map_err(|error| error.error.node);
+
+		for expression in ["1 - (2 - 3) * 4 % 3", "-2 ^ -(1 + 1)!", "ncr (round 2.5) 2",
+			"e ^ (1 / 3) - (2 * pi) / 7", "10 / (0 * 2)", "[[1, 2], [3, 4]] ^ (1 + 1)"] {
+			assert_eq!(evaluate(&super::format(expression).unwrap()), evaluate(expression));
+		}
+	}
+}
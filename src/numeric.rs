@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+
+/// Coefficients for the Lanczos approximation with `g = 7`.
+const LANCZOS: [f64; 9] = [
+	0.999_999_999_999_809_9,
+	676.520_368_121_885_1,
+	-1_259.139_216_722_402_8,
+	771.323_428_777_653_1,
+	-176.615_029_162_140_6,
+	12.507_343_278_686_905,
+	-0.138_571_095_265_720_12,
+	9.984_369_578_019_572e-6,
+	1.505_632_735_149_311_6e-7,
+];
+
+/// The largest integer with a factorial that is finite.
+const MAXIMUM_FACTORIAL: f64 = 170.0;
+
+pub fn is_integer(value: f64) -> bool {
+	value.fract() == 0.0
+}
+
+/// Evaluates the gamma function with the Lanczos approximation.
+/// Arguments less than one half use the reflection formula.
+pub fn gamma(value: f64) -> f64 {
+	use std::f64::consts::PI;
+	if is_integer(value) && value > 0.0 {
+		return factorial(value - 1.0);
+	} else if value < 0.5 {
+		return PI / ((PI * value).sin() * gamma(1.0 - value));
+	}
+
+	let value = value - 1.0;
+	let base = value + LANCZOS.len() as f64 - 1.5;
+	let series = LANCZOS.iter().enumerate().skip(1)
+		.fold(LANCZOS[0], |series, (index, coefficient)|
+			series + coefficient / (value + index as f64));
+	(2.0 * PI).sqrt() * base.powf(value + 0.5) * (-base).exp() * series
+}
+
+/// Evaluates the factorial exactly for non-negative integers
+/// and with the gamma function otherwise.
+pub fn factorial(value: f64) -> f64 {
+	match is_integer(value) && value >= 0.0 {
+		true if value > MAXIMUM_FACTORIAL => f64::INFINITY,
+		true => (2..=value as u64).fold(1.0, |product, factor| product * factor as f64),
+		false => gamma(value + 1.0),
+	}
+}
+
+/// Counts the ways to choose `choose` items from `total` items.
+/// Both arguments must be non-negative integers.
+pub fn binomial(total: f64, choose: f64) -> f64 {
+	if choose > total {
+		return 0.0;
+	}
+
+	let choose = choose.min(total - choose) as u64;
+	let combinations = (1..=choose).fold(1.0, |product, index|
+		product * (total - choose as f64 + index as f64) / index as f64);
+	combinations.round()
+}
+
+/// Counts the ordered arrangements of `choose` items from `total` items.
+/// Both arguments must be non-negative integers.
+pub fn permutation(total: f64, choose: f64) -> f64 {
+	match choose > total {
+		true => 0.0,
+		false => (0..choose as u64).fold(1.0, |product, index| product * (total - index as f64)),
+	}
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum Rounding {
+	/// Ties are rounded away from zero.
+	#[default]
+	HalfUp,
+	/// Ties are rounded to the nearest even digit.
+	HalfEven,
+}
+
+impl Rounding {
+	pub fn parse(string: &str) -> Option<Rounding> {
+		match string {
+			"half-up" => Some(Rounding::HalfUp),
+			"half-even" => Some(Rounding::HalfEven),
+			_ => None,
+		}
+	}
+}
+
+/// Rounds a value so that its last digit has a place value of
+/// `10 ^ -places`. Rounding is applied to the shortest decimal
+/// representation of the value so that decimal ties are exact.
+pub fn round(value: f64, places: i64, rounding: Rounding) -> f64 {
+	if value == 0.0 || !value.is_finite() {
+		return value;
+	}
+
+	let (digits, exponent) = decimal(value);
+	let kept = exponent.saturating_add(places).saturating_add(1);
+	if kept >= digits.len() as i64 {
+		return value;
+	} else if kept < 0 {
+		return 0.0;
+	}
+
+	let (kept, remainder) = digits.split_at(kept as usize);
+	let integer = kept.iter().fold(0, |integer, digit| integer * 10 + *digit as u64);
+	let round_up = match remainder[0].cmp(&5) {
+		Ordering::Greater => true,
+		Ordering::Less => false,
+		Ordering::Equal if remainder[1..].iter().any(|digit| *digit != 0) => true,
+		Ordering::Equal => match rounding {
+			Rounding::HalfUp => true,
+			Rounding::HalfEven => integer % 2 == 1,
+		},
+	};
+
+	let integer = integer + round_up as u64;
+	let exponent = exponent + 1 - kept.len() as i64;
+	format!("{}e{}", integer, exponent).parse::<f64>().unwrap().copysign(value)
+}
+
+/// Rounds a value to a number of significant figures.
+pub fn round_significant(value: f64, figures: i64, rounding: Rounding) -> f64 {
+	match value == 0.0 || !value.is_finite() {
+		true => value,
+		false => round(value, figures - 1 - decimal(value).1, rounding),
+	}
+}
+
+/// Formats a number in decimal notation. Numbers that are very
+/// small or large are formatted in scientific notation.
+pub fn plain(number: f64) -> String {
+	let exponentiation_range = 1e-3 < number.abs() && number.abs() < 1e9;
+	match exponentiation_range || number == 0.0 || !number.is_finite() {
+		true => number.to_string(),
+		false => format!("{:e}", number),
+	}
+}
+
+/// Decomposes the magnitude of a value into the digits of its shortest
+/// decimal representation and the exponent of the first digit.
+fn decimal(value: f64) -> (Vec<u8>, i64) {
+	let string = format!("{:e}", value.abs());
+	let (mantissa, exponent) = string.split_at(string.find('e').unwrap());
+	let digits = mantissa.bytes().filter(u8::is_ascii_digit)
+		.map(|digit| digit - b'0').collect();
+	(digits, exponent[1..].parse().unwrap())
+}
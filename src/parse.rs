@@ -2,19 +2,23 @@ use crate::coalescence::Coalescence;
 use crate::item::Function;
 use crate::node::Node;
 use crate::span::{Span, Spanned};
-use crate::token::Operator;
+use crate::token::{Associativity, NEGATION_PRECEDENCE, Operator};
 
 #[derive(Debug)]
 enum ParserOperator {
 	Operator(Spanned<Operator>),
-	Function(Spanned<Function>),
+	Function(Spanned<Function>, usize),
+	/// A unary minus that waits for the operators that bind tighter
+	/// with the number of nodes before its operand.
+	Negation(Spanned<Function>, usize),
 }
 
 impl ParserOperator {
 	pub fn precedence(&self) -> usize {
 		match self {
 			ParserOperator::Operator(operator) => operator.node.precedence(),
-			ParserOperator::Function(_) => usize::max_value(),
+			ParserOperator::Function(_, _) => usize::MAX,
+			ParserOperator::Negation(_, _) => NEGATION_PRECEDENCE,
 		}
 	}
 }
@@ -29,15 +33,27 @@ pub fn parse_root(coalescence: Coalescence) -> Spanned<Node> {
 fn parse(coalescence: Coalescence, operators: &mut Vec<ParserOperator>,
          state: usize, nodes: &mut Vec<Spanned<Node>>) {
 	match coalescence {
-		Coalescence::Terminal(terminal) =>
-			nodes.push(Spanned::new(Node::Terminal(terminal.node), terminal.span)),
-		Coalescence::Variable(variable) =>
-			nodes.push(Spanned::new(Node::Variable(variable.node), variable.span)),
-		Coalescence::Function(function) =>
-			operators.push(ParserOperator::Function(function)),
+		Coalescence::Terminal(terminal) => {
+			nodes.push(Spanned::new(Node::Terminal(terminal.node), terminal.span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Variable(variable) => {
+			nodes.push(Spanned::new(Node::Variable(variable.node), variable.span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Function(function) => match function.node {
+			Function::UnaryMinus => operators.push(ParserOperator::Negation(function, nodes.len())),
+			_ => operators.push(ParserOperator::Function(function, nodes.len())),
+		},
 		Coalescence::Operator(operator) => {
+			let precedence = operator.node.precedence();
 			while let Some(stack_operator) = operators.last() {
-				match stack_operator.precedence() >= operator.node.precedence() {
+				let binds = match operator.node.associativity() {
+					Associativity::Left => stack_operator.precedence() >= precedence,
+					Associativity::Right => stack_operator.precedence() > precedence,
+				};
+
+				match binds {
 					true if operators.len() > state => construct(operators, nodes),
 					_ => break,
 				}
@@ -53,6 +69,56 @@ fn parse(coalescence: Coalescence, operators: &mut Vec<ParserOperator>,
 			while operators.len() > operator_state {
 				construct(operators, nodes);
 			}
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Postfix(coalescence, function) => {
+			parse(*coalescence, operators, operators.len(), nodes);
+			let node = nodes.pop().unwrap();
+			let span = Span(node.span.byte_start(), function.span.byte_end());
+			nodes.push(Spanned::new(Node::Function(function, vec![node]), span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::List(elements, span) => {
+			let elements = elements.into_iter().map(parse_root).collect();
+			nodes.push(Spanned::new(Node::List(elements), span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Identifier(identifier) => {
+			nodes.push(Spanned::new(Node::Identifier(identifier.node), identifier.span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Call(function, arguments, span) => {
+			let arguments = arguments.into_iter().map(parse_root).collect();
+			nodes.push(Spanned::new(Node::Function(function, arguments), span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Conditional(condition, consequent, alternative, span) => {
+			let node = Node::Conditional(Box::new(parse_root(*condition)),
+				Box::new(parse_root(*consequent)), Box::new(parse_root(*alternative)));
+			nodes.push(Spanned::new(node, span));
+			reduce(operators, state, nodes);
+		}
+		Coalescence::Range(start, end) => {
+			let span = Span(start.span.byte_start(), end.span.byte_end());
+			nodes.push(Spanned::new(Node::Range(start, end), span));
+			reduce(operators, state, nodes);
+		}
+	}
+}
+
+/// Constructs every function on top of the stack that has all of
+/// its arguments. Functions are applied as soon as possible as
+/// their arguments are terms that bind tighter than any operator.
+/// A negation is complete once a term follows its operand as no
+/// operator can then apply to the operand.
+fn reduce(operators: &mut Vec<ParserOperator>, state: usize, nodes: &mut Vec<Spanned<Node>>) {
+	while operators.len() > state {
+		match operators.last() {
+			Some(ParserOperator::Negation(_, base)) if nodes.len() > base + 1 =>
+				construct(operators, nodes),
+			Some(ParserOperator::Function(function, base))
+				if nodes.len() >= base + function.node.arity() => construct(operators, nodes),
+			_ => break,
 		}
 	}
 }
@@ -67,10 +133,16 @@ fn construct(operators: &mut Vec<ParserOperator>, nodes: &mut Vec<Spanned<Node>>
 			let node = Node::Operator(operator, Box::new(left), Box::new(right));
 			nodes.push(Spanned::new(node, span))
 		}
-		ParserOperator::Function(function) => {
-			let node = nodes.pop().unwrap();
-			let span = Span(function.span.byte_start(), node.span.byte_end());
-			let node = Node::Function(function.node, Box::new(node));
+		ParserOperator::Negation(function, base) => {
+			let argument = nodes.remove(base);
+			let span = Span(function.span.byte_start(), argument.span.byte_end());
+			nodes.insert(base, Spanned::new(Node::Function(function, vec![argument]), span))
+		}
+		ParserOperator::Function(function, _) => {
+			let arguments = nodes.split_off(nodes.len() - function.node.arity());
+			let byte_end = arguments.last().unwrap().span.byte_end();
+			let span = Span(function.span.byte_start(), byte_end);
+			let node = Node::Function(function, arguments);
 			nodes.push(Spanned::new(node, span))
 		}
 	}
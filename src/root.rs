@@ -0,0 +1,265 @@
+use std::f64::consts::TAU;
+
+use crate::complex::Complex;
+
+/// The number of subintervals that are scanned for sign changes.
+const SAMPLES: usize = 1000;
+
+/// The largest number of iterations used to refine a root.
+const ITERATIONS: usize = 100;
+
+/// The relative change between iterations at which a root is accepted.
+const TOLERANCE: f64 = 1e-14;
+
+/// The largest magnitude of a function at an accepted root. Sign
+/// changes across discontinuities exceed this and are discarded.
+const RESIDUAL: f64 = 1e-6;
+
+/// The largest number of iterations used to find the roots of a polynomial.
+const POLYNOMIAL_ITERATIONS: usize = 500;
+
+/// The largest error of an accepted root of a polynomial relative
+/// to the magnitudes of the terms of the polynomial at the root.
+const BACKWARD_ERROR: f64 = 1e-8;
+
+/// Finds the roots of a function between two bounds by scanning for
+/// sign changes and refining each bracketed root. Points at which the
+/// function cannot be evaluated are skipped. Returns the first error
+/// of the function if no roots are found and the function failed.
+pub fn roots<E>(function: &mut impl FnMut(f64) -> Result<f64, E>,
+                lower: f64, upper: f64) -> Result<Vec<f64>, E> {
+	let mut roots = Vec::new();
+	let mut failure = None;
+	let mut previous: Option<(f64, f64)> = None;
+	for index in 0..=SAMPLES {
+		let point = lower + (upper - lower) * (index as f64 / SAMPLES as f64);
+		let value = match function(point) {
+			Ok(value) if value.is_finite() => value,
+			Ok(_) => {
+				previous = None;
+				continue;
+			}
+			Err(error) => {
+				failure.get_or_insert(error);
+				previous = None;
+				continue;
+			}
+		};
+
+		if value == 0.0 {
+			roots.push(point);
+		} else if let Some((last_point, last_value)) = previous {
+			if last_value != 0.0 && last_value.signum() != value.signum() {
+				match bracketed(function, (last_point, last_value), (point, value)) {
+					Ok(Some(root)) => roots.push(root),
+					Ok(None) => (),
+					Err(error) => {
+						failure.get_or_insert(error);
+					}
+				}
+			}
+		}
+		previous = Some((point, value));
+	}
+
+	match (roots.is_empty(), failure) {
+		(true, Some(error)) => Err(error),
+		_ => Ok(roots),
+	}
+}
+
+/// Refines a root between two points at which the function has opposite
+/// signs with Newton iterations that fall back to bisection whenever an
+/// iteration leaves the bracket. Returns `None` if the sign change is
+/// not a root such as at a discontinuity.
+fn bracketed<E>(function: &mut impl FnMut(f64) -> Result<f64, E>,
+                (mut lower, lower_value): (f64, f64), (mut upper, _): (f64, f64))
+                -> Result<Option<f64>, E> {
+	let mut point = (lower + upper) / 2.0;
+	for _ in 0..ITERATIONS {
+		let value = function(point)?;
+		if value == 0.0 {
+			return Ok(Some(point));
+		} else if value.signum() == lower_value.signum() {
+			lower = point;
+		} else {
+			upper = point;
+		}
+
+		let newton = point - value / slope(function, point)?;
+		let next = match lower <= newton && newton <= upper {
+			true => newton,
+			false => (lower + upper) / 2.0,
+		};
+
+		let converged = (next - point).abs() <= TOLERANCE * point.abs().max(1.0);
+		point = next;
+		if converged {
+			break;
+		}
+	}
+
+	let residual = function(point)?;
+	Ok(Some(point).filter(|_| residual.abs() <= RESIDUAL))
+}
+
+/// Finds a root of a function with Newton iterations from an initial
+/// guess. Returns `None` if the iterations do not converge to a root.
+pub fn newton<E>(function: &mut impl FnMut(f64) -> Result<f64, E>,
+                 guess: f64) -> Result<Option<f64>, E> {
+	let mut point = guess;
+	for _ in 0..ITERATIONS {
+		let value = function(point)?;
+		if value == 0.0 {
+			return Ok(Some(point));
+		}
+
+		let next = point - value / slope(function, point)?;
+		if !next.is_finite() {
+			return Ok(None);
+		} else if (next - point).abs() <= TOLERANCE * point.abs().max(1.0) {
+			let residual = function(next)?;
+			return Ok(Some(next).filter(|_| residual.abs() <= RESIDUAL));
+		}
+		point = next;
+	}
+	Ok(None)
+}
+
+/// Finds every root of a polynomial from its coefficients in order of
+/// decreasing degree. The coefficients must not all be zero. Polynomials
+/// of degree at most two are solved in closed form and others with the
+/// Aberth method. Returns `None` if the iterations do not converge.
+pub fn polynomial(coefficients: &[f64]) -> Option<Vec<Complex>> {
+	let start = coefficients.iter().position(|coefficient| *coefficient != 0.0)?;
+	let end = coefficients.iter().rposition(|coefficient| *coefficient != 0.0)?;
+	let mut roots = vec![Complex::default(); coefficients.len() - 1 - end];
+	roots.extend(match &coefficients[start..=end] {
+		[_] => Vec::new(),
+		[a, b] => vec![Complex::real(-b / a)],
+		[a, b, c] => quadratic(*a, *b, *c),
+		coefficients => aberth(coefficients)?,
+	});
+
+	roots.sort_by(|left, right| left.real.total_cmp(&right.real)
+		.then(left.imaginary.total_cmp(&right.imaginary)));
+	Some(roots)
+}
+
+/// Solves a quadratic with a non-zero constant term in a form
+/// that avoids cancellation between the terms of the numerator.
+fn quadratic(a: f64, b: f64, c: f64) -> Vec<Complex> {
+	let discriminant = b * b - 4.0 * a * c;
+	if discriminant < 0.0 {
+		let (real, imaginary) = (-b / (2.0 * a), (-discriminant).sqrt() / (2.0 * a));
+		return vec![Complex::new(real, imaginary), Complex::new(real, -imaginary)];
+	}
+
+	let q = -(b + b.signum() * discriminant.sqrt()) / 2.0;
+	vec![Complex::real(q / a), Complex::real(c / q)]
+}
+
+/// Finds the roots of a polynomial with a non-zero constant term by
+/// simultaneously refining an approximation of every root.
+fn aberth(coefficients: &[f64]) -> Option<Vec<Complex>> {
+	let degree = coefficients.len() - 1;
+	let radius = 1.0 + coefficients[1..].iter()
+		.map(|coefficient| (coefficient / coefficients[0]).abs()).fold(0.0, f64::max);
+	let mut roots: Vec<_> = (0..degree).map(|index|
+		Complex::polar(radius, TAU * index as f64 / degree as f64 + 0.4)).collect();
+
+	for _ in 0..POLYNOMIAL_ITERATIONS {
+		let mut converged = true;
+		for index in 0..degree {
+			let (value, derivative) = horner(coefficients, roots[index]);
+			let ratio = value / derivative;
+			let repulsion = (0..degree).filter(|other| *other != index)
+				.fold(Complex::default(), |sum, other|
+					sum + Complex::real(1.0) / (roots[index] - roots[other]));
+
+			let correction = ratio / (Complex::real(1.0) - ratio * repulsion);
+			if value != Complex::default() {
+				roots[index] = roots[index] - correction;
+				converged &= correction.magnitude() <= TOLERANCE * roots[index].magnitude();
+			}
+		}
+
+		if converged {
+			break;
+		}
+	}
+
+	let magnitudes: Vec<_> = coefficients.iter().map(|coefficient| coefficient.abs()).collect();
+	let accepted = roots.iter().all(|root| {
+		let (value, _) = horner(coefficients, *root);
+		let (scale, _) = horner(&magnitudes, Complex::real(root.magnitude()));
+		value.magnitude() <= BACKWARD_ERROR * scale.real
+	});
+
+	accepted.then(|| roots.into_iter().map(|root| match root.imaginary.abs() {
+		imaginary if imaginary <= BACKWARD_ERROR * root.magnitude() => Complex::real(root.real),
+		_ => root,
+	}).collect())
+}
+
+/// Evaluates a polynomial and its derivative at a point.
+fn horner(coefficients: &[f64], point: Complex) -> (Complex, Complex) {
+	let (mut value, mut derivative) = (Complex::default(), Complex::default());
+	for coefficient in coefficients {
+		derivative = derivative * point + value;
+		value = value * point + Complex::real(*coefficient);
+	}
+	(value, derivative)
+}
+
+/// Approximates the derivative of a function with a central difference.
+fn slope<E>(function: &mut impl FnMut(f64) -> Result<f64, E>, point: f64) -> Result<f64, E> {
+	let step = 1e-7 * point.abs().max(1.0);
+	Ok((function(point + step)? - function(point - step)?) / (2.0 * step))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn exact(value: f64) -> Result<f64, ()> {
+		Ok(value)
+	}
+
+	#[test]
+	fn test_roots() {
+		let roots = roots(&mut |x| exact(x * x - 2.0), -10.0, 10.0).unwrap();
+		assert_eq!(roots.len(), 2);
+		assert!((roots[0] + 2f64.sqrt()).abs() < 1e-12);
+		assert!((roots[1] - 2f64.sqrt()).abs() < 1e-12);
+		assert_eq!(super::roots(&mut |x| exact(x.tan()), 1.0, 2.0).unwrap(), &[]);
+		assert_eq!(super::roots(&mut |x| exact(x - 5.0), -10.0, 10.0).unwrap(), &[5.0]);
+	}
+
+	#[test]
+	fn test_polynomial() {
+		let approximate = |coefficients: &[f64], expected: &[(f64, f64)]| {
+			let roots = polynomial(coefficients).unwrap();
+			roots.len() == expected.len() && roots.iter().zip(expected).all(|(root, expected)|
+				(*root - Complex::new(expected.0, expected.1)).magnitude() < 1e-7)
+		};
+
+		let root = 2f64.sqrt();
+		assert!(approximate(&[1.0, 0.0, -2.0], &[(-root, 0.0), (root, 0.0)]));
+		assert!(approximate(&[1.0, 0.0, 1.0], &[(0.0, -1.0), (0.0, 1.0)]));
+		assert!(approximate(&[0.0, 2.0, -6.0], &[(3.0, 0.0)]));
+		assert!(approximate(&[1.0, -6.0, 11.0, -6.0], &[(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]));
+		assert!(approximate(&[1.0, 0.0, 0.0, 0.0, -1.0],
+			&[(-1.0, 0.0), (0.0, -1.0), (0.0, 1.0), (1.0, 0.0)]));
+		assert!(approximate(&[1.0, 0.0, -3.0, 2.0, 0.0], &[(-2.0, 0.0), (0.0, 0.0),
+			(1.0, 0.0), (1.0, 0.0)]));
+		assert_eq!(polynomial(&[4.0]), Some(Vec::new()));
+	}
+
+	#[test]
+	fn test_newton() {
+		let root = newton(&mut |x| exact(x.cos() - x), 1.0).unwrap().unwrap();
+		assert!((root - 0.7390851332151607).abs() < 1e-12);
+		assert_eq!(newton(&mut |x| exact(x * x + 1.0), 1.0).unwrap(), None);
+	}
+}
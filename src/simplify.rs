@@ -0,0 +1,172 @@
+use crate::context::Context;
+use crate::item::Function;
+use crate::node::Node;
+use crate::span::{Span, Spanned};
+use crate::token::Operator;
+use crate::value::Value;
+
+/// Simplifies a node by evaluating the terms that do not depend on
+/// variables and by removing operations that leave a number unchanged.
+/// Terms that cannot be evaluated are kept so that their errors are
+/// reported when the simplified node is evaluated.
+pub fn simplify(node: &Spanned<Node>, context: &Context) -> Spanned<Node> {
+	let span = node.span;
+	let all = |nodes: &[Spanned<Node>]| nodes.iter()
+		.map(|node| simplify(node, context)).collect();
+	let node = Spanned::new(match &node.node {
+		Node::Function(function, arguments) => Node::Function(function.clone(), all(arguments)),
+		Node::Operator(operator, left, right) => Node::Operator(*operator,
+			Box::new(simplify(left, context)), Box::new(simplify(right, context))),
+		Node::List(elements) => Node::List(all(elements)),
+		Node::Conditional(condition, consequent, alternative) => Node::Conditional(
+			Box::new(simplify(condition, context)), Box::new(simplify(consequent, context)),
+			Box::new(simplify(alternative, context))),
+		node => node.clone(),
+	}, span);
+
+	if literal(&node.node).is_none() && constant(&node.node, &[]) {
+		let value = match node.evaluate(context) {
+			Ok(Value::Number(value) | Value::Estimate(value, _)) => Some(value),
+			_ => None,
+		};
+
+		if let Some(value) = value.filter(|value| value.is_finite()) {
+			return number(value, span);
+		}
+	}
+	identity(node)
+}
+
+/// Removes an operation that leaves its operand unchanged.
+fn identity(node: Spanned<Node>) -> Spanned<Node> {
+	let span = node.span;
+	match node.node {
+		Node::Operator(operator, left, right) => {
+			let (left_literal, right_literal) = (literal(&left.node), literal(&right.node));
+			match operator.node {
+				Operator::Multiply | Operator::Divide | Operator::Power
+					if right_literal == Some(1.0) && scalar(&left.node) => *left,
+				Operator::Add | Operator::Minus
+					if right_literal == Some(0.0) && scalar(&left.node) => *left,
+				Operator::Multiply if left_literal == Some(1.0) && scalar(&right.node) => *right,
+				Operator::Add if left_literal == Some(0.0) && scalar(&right.node) => *right,
+				_ => Spanned::new(Node::Operator(operator, left, right), span),
+			}
+		}
+		Node::Function(function, mut arguments) => match arguments.pop() {
+			Some(Spanned { node: Node::Function(inner, mut inner_arguments), .. })
+				if function.node == Function::UnaryMinus && inner.node == Function::UnaryMinus
+					&& scalar(&inner_arguments[0].node) => inner_arguments.pop().unwrap(),
+			Some(argument) => {
+				arguments.push(argument);
+				Spanned::new(Node::Function(function, arguments), span)
+			}
+			None => Spanned::new(Node::Function(function, arguments), span),
+		},
+		Node::Conditional(condition, consequent, alternative) =>
+			match literal(&condition.node) {
+				Some(condition) if condition != 0.0 => *consequent,
+				Some(_) => *alternative,
+				None => Spanned::new(Node::Conditional(condition, consequent, alternative), span),
+			},
+		node => Spanned::new(node, span),
+	}
+}
+
+/// Writes a number as a node. Negative numbers are the negation of a
+/// terminal as the terminals of an expression are never negative.
+fn number(value: f64, span: Span) -> Spanned<Node> {
+	let terminal = Spanned::new(Node::Terminal(value.abs()), span);
+	match value < 0.0 {
+		false => terminal,
+		true => Spanned::new(Node::Function(Spanned::new(Function::UnaryMinus,
+			Span(span.byte_start(), span.byte_start() + 1)), vec![terminal]), span),
+	}
+}
+
+/// Finds the number that a terminal or a negated terminal represents.
+pub fn literal(node: &Node) -> Option<f64> {
+	match node {
+		Node::Terminal(terminal) => Some(*terminal),
+		Node::Function(function, arguments) if function.node == Function::UnaryMinus =>
+			match arguments[0].node {
+				Node::Terminal(terminal) => Some(-terminal),
+				_ => None,
+			},
+		_ => None,
+	}
+}
+
+/// Whether a node depends only on terminals and the
+/// identifiers that are bound within the node.
+fn constant(node: &Node, bound: &[String]) -> bool {
+	match node {
+		Node::Terminal(_) => true,
+		Node::Variable(_) | Node::Range(_, _) => false,
+		Node::Identifier(identifier) => bound.contains(identifier),
+		Node::Function(function, arguments) => {
+			let bound = match &function.node {
+				Function::Series(_, variable) | Function::Calculus(_, variable) =>
+					[bound, std::slice::from_ref(variable)].concat(),
+				_ => bound.to_vec(),
+			};
+			arguments.iter().all(|argument| constant(&argument.node, &bound))
+		}
+		Node::Operator(_, left, right) => constant(&left.node, bound) &&
+			constant(&right.node, bound),
+		Node::List(elements) => elements.iter().all(|element| constant(&element.node, bound)),
+		Node::Conditional(condition, consequent, alternative) =>
+			[condition, consequent, alternative].iter()
+				.all(|node| constant(&node.node, bound)),
+	}
+}
+
+/// Whether a node evaluates to a number if it evaluates at all.
+/// Operations on a number can be removed only if they leave the
+/// number unchanged, whereas lists, matrices and error estimates
+/// may be changed or rejected by the operation.
+fn scalar(node: &Node) -> bool {
+	match node {
+		Node::Terminal(_) | Node::Identifier(_) => true,
+		Node::Variable(_) | Node::Range(_, _) | Node::List(_) => false,
+		Node::Function(function, arguments) => match &function.node {
+			Function::Calculus(_, _) => false,
+			Function::Series(_, _) => true,
+			_ => arguments.iter().all(|argument| scalar(&argument.node)),
+		},
+		Node::Operator(_, left, right) => scalar(&left.node) && scalar(&right.node),
+		Node::Conditional(_, consequent, alternative) =>
+			scalar(&consequent.node) && scalar(&alternative.node),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn simplified(expression: &str) -> String {
+		let node = crate::tests::parse(expression, &[]);
+		crate::notation::formatted(&simplify(&node, &Context::default()).node)
+	}
+
+	#[test]
+	fn test_folding() {
+		assert_eq!(simplified("2 * 3 + 4"), "10");
+		assert_eq!(simplified("2 - 5"), "-3");
+		assert_eq!(simplified("$ + 2 * 3"), "$ + 6");
+		assert_eq!(simplified("sum(k, 1, 3, k * (1 + 1))"), "12");
+		assert_eq!(simplified("sum(k, 1, $, k * (1 + 1))"), "sum(k, 1, $, k * 2)");
+		assert_eq!(simplified("$ / (2 - 2)"), "$ / 0");
+		assert_eq!(simplified("1 / 0 + $"), "1 / 0 + $");
+		assert_eq!(simplified("mean [1, 2 * 3] + $"), "3.5 + $");
+	}
+
+	#[test]
+	fn test_identities() {
+		assert_eq!(simplified("sum(k, 1, $, k * 1 + 0)"), "sum(k, 1, $, k)");
+		assert_eq!(simplified("sum(k, 1, $, (1 * k - 0) ^ 1 / 1)"), "sum(k, 1, $, k)");
+		assert_eq!(simplified("sum(k, 1, $, --k + (0 + k))"), "sum(k, 1, $, k + k)");
+		assert_eq!(simplified("sum(k, 1, $, if 1 < 2 then k else 0)"), "sum(k, 1, $, k)");
+		assert_eq!(simplified("$ * 1 + 0"), "$ * 1 + 0");
+	}
+}
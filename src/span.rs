@@ -1,6 +1,6 @@
 use std::fmt;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Span(pub usize, pub usize);
 
 impl Span {
@@ -22,7 +22,7 @@ impl fmt::Debug for Span {
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Spanned<T> {
 	pub node: T,
 	pub span: Span,
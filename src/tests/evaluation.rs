@@ -1,5 +1,11 @@
+use crate::complex::Complex;
 use crate::context::Context;
 use crate::error::Error;
+use crate::item::{AngleUnit, Statistic};
+use crate::matrix::Matrix;
+use crate::numeric::Rounding;
+use crate::span::Span;
+use crate::value::Value;
 
 #[test]
 fn test_arithmetic() {
@@ -8,6 +14,9 @@ fn test_arithmetic() {
 	assert_eq!(evaluate("1 * 2"), Ok(2.0));
 	assert_eq!(evaluate("1 / 2"), Ok(0.5));
 	assert_eq!(evaluate("10 ^ -2"), Ok(0.01));
+	assert_eq!(evaluate("2 ^ 3 ^ 2"), Ok(512.0));
+	assert_eq!(evaluate("2 ^ -1 ^ 2"), Ok(0.5));
+	assert_eq!(evaluate("8 / 4 / 2"), Ok(1.0));
 }
 
 #[test]
@@ -16,6 +25,17 @@ fn test_unary_minus() {
 	assert_eq!(evaluate("1 - -1"), Ok(2.0));
 	assert_eq!(evaluate("1 --- 1"), Ok(0.0));
 	assert_eq!(evaluate("-1 -- -1"), Ok(-2.0));
+	assert_eq!(evaluate("-2 ^ 2"), Ok(-4.0));
+	assert_eq!(evaluate("(-2) ^ 2"), Ok(4.0));
+	assert_eq!(evaluate("-2 * 3 ^ 2"), Ok(-18.0));
+	assert_eq!(evaluate("abs -2 + 1"), Ok(3.0));
+	assert_eq!(evaluate("round -2.675 2"), Ok(-2.68));
+	assert_eq!(evaluate("modinv -3 11"), Ok(7.0));
+	assert_eq!(evaluate("sigfig -1234 2"), Ok(-1200.0));
+	assert_eq!(evaluate("powmod -2 3 5"), Ok(2.0));
+	assert_eq!(evaluate("powmod --2 3 5"), Ok(3.0));
+	assert_eq!(evaluate("modinv -sqrt 9 11"), Ok(7.0));
+	assert_eq!(evaluate("ncr 5 -2"), Err(Error::Domain));
 }
 
 #[test]
@@ -26,6 +46,47 @@ fn test_functions() {
 	assert_eq!(evaluate("sqrt -1 + 1;;"), Ok(0.0));
 }
 
+#[test]
+fn test_trigonometry() {
+	let approximate = |expression: &str, value: f64|
+		(evaluate(expression).unwrap() - value).abs() < 1e-12;
+	assert!(approximate("sec 0", 1.0));
+	assert!(approximate("csc' 90", 1.0));
+	assert!(approximate("cot' 45", 1.0));
+	assert!(approximate("asec' 2", 60.0));
+	assert!(approximate("acot 0", std::f64::consts::FRAC_PI_2));
+	assert!(approximate("sin'g 100", 1.0));
+	assert!(approximate("acos'g 0", 100.0));
+	assert!(approximate("cosh asinh 1", 2.0_f64.sqrt()));
+	assert!(approximate("tanh atanh 0.5", 0.5));
+	assert_eq!(evaluate("cot 0"), Err(Error::Domain));
+	assert_eq!(evaluate("sec' 90"), Err(Error::Domain));
+	assert_eq!(evaluate("sec' -270"), Err(Error::Domain));
+	assert_eq!(evaluate("tan'g 100"), Err(Error::Domain));
+	assert_eq!(evaluate("csc' 180"), Err(Error::Domain));
+	assert_eq!(evaluate("cot'g 400"), Err(Error::Domain));
+	assert!(approximate("sec' 180", -1.0));
+	assert_eq!(evaluate("acosh 0.5"), Err(Error::Domain));
+	assert_eq!(evaluate("atanh 1"), Err(Error::Domain));
+	assert_eq!(evaluate("sinh' 1"), Err(Error::InvalidItem));
+	assert_eq!(evaluate("sin'x 1"), Err(Error::InvalidItem));
+}
+
+#[test]
+fn test_angle_mode() {
+	let context = &mut Context::default();
+	context.angle_unit = AngleUnit::Degrees;
+	let mut evaluate = |expression: &str| {
+		context.expression = expression.to_owned();
+		crate::interface::evaluate(context).map_err(|error| error.error.node)
+	};
+
+	assert_eq!(evaluate("sin 90"), Ok(Value::Number(1.0)));
+	assert_eq!(evaluate("asin 1"), Ok(Value::Number(90.0)));
+	assert_eq!(evaluate("sin'r 0"), Ok(Value::Number(0.0)));
+	assert_eq!(evaluate("acos'r 1"), Ok(Value::Number(0.0)));
+}
+
 #[test]
 fn test_implicit() {
 	assert_eq!(evaluate("1 (2)"), Ok(2.0));
@@ -36,9 +97,298 @@ fn test_implicit() {
 	assert_eq!(evaluate("(abs -1) 2"), Ok(2.0));
 }
 
+#[test]
+fn test_combinatorics() {
+	assert_eq!(evaluate("5!"), Ok(120.0));
+	assert_eq!(evaluate("-3!"), Ok(-6.0));
+	assert_eq!(evaluate("3!!"), Ok(720.0));
+	assert_eq!(evaluate("2 (3)!"), Ok(12.0));
+	assert_eq!(evaluate("(3)! 2"), Ok(12.0));
+	assert_eq!(evaluate("1 + 2;!"), Ok(6.0));
+	assert_eq!(evaluate("(-1)!"), Err(Error::NegativeFactorial));
+	assert_eq!(evaluate("gamma 5"), Ok(24.0));
+	assert_eq!(evaluate("gamma 0"), Err(Error::Domain));
+	assert!((evaluate("0.5!").unwrap() - std::f64::consts::PI.sqrt() / 2.0).abs() < 1e-12);
+
+	assert_eq!(evaluate("ncr 5 2"), Ok(10.0));
+	assert_eq!(evaluate("nPr 5 2"), Ok(20.0));
+	assert_eq!(evaluate("ncr sqrt 16 2 + 1"), Ok(7.0));
+	assert_eq!(evaluate("ncr 5 2 + 1;"), Ok(10.0));
+	assert_eq!(evaluate("ncr 5.5 2"), Err(Error::NonInteger));
+	assert_eq!(evaluate("ncr 5 + 2"), Err(Error::ExpectedValued));
+}
+
+#[test]
+fn test_rounding() {
+	assert_eq!(evaluate("floor -2.5"), Ok(-3.0));
+	assert_eq!(evaluate("ceil -2.5"), Ok(-2.0));
+	assert_eq!(evaluate("trunc -2.5"), Ok(-2.0));
+	assert_eq!(evaluate("frac -2.5"), Ok(-0.5));
+	assert_eq!(evaluate("sign -2.5"), Ok(-1.0));
+	assert_eq!(evaluate("round 2.5"), Ok(3.0));
+	assert_eq!(evaluate("round -2.5"), Ok(-3.0));
+	assert_eq!(evaluate("round 2.675 2"), Ok(2.68));
+	assert_eq!(evaluate("round 1250 (-2)"), Ok(1300.0));
+	assert_eq!(evaluate("round (2.675) (2)"), Ok(2.68));
+	assert_eq!(evaluate("round 2.675 2 + 1"), Ok(3.68));
+	assert_eq!(evaluate("sigfig 0.012345 3"), Ok(0.0123));
+	assert_eq!(evaluate("round 2.5 0.5"), Err(Error::NonInteger));
+	assert_eq!(evaluate("sigfig 2.5 0"), Err(Error::Domain));
+
+	let context = &mut Context::default();
+	context.rounding = Rounding::HalfEven;
+	let mut evaluate = |expression: &str| {
+		context.expression = expression.to_owned();
+		crate::interface::evaluate(context).map_err(|error| error.error.node)
+	};
+
+	assert_eq!(evaluate("round 2.5"), Ok(Value::Number(2.0)));
+	assert_eq!(evaluate("round 3.5"), Ok(Value::Number(4.0)));
+	assert_eq!(evaluate("round 2.665 2"), Ok(Value::Number(2.66)));
+	assert_eq!(evaluate("sigfig 1250 2"), Ok(Value::Number(1200.0)));
+}
+
+#[test]
+fn test_number_theory() {
+	assert_eq!(evaluate("gcd 12 -18"), Ok(6.0));
+	assert_eq!(evaluate("lcm 4 6"), Ok(12.0));
+	assert_eq!(evaluate("isprime 97"), Ok(1.0));
+	assert_eq!(evaluate("isprime 91"), Ok(0.0));
+	assert_eq!(evaluate("nextprime 97"), Ok(101.0));
+	assert_eq!(evaluate("powmod 2 10 1000"), Ok(24.0));
+	assert_eq!(evaluate("powmod (-2) 3 5"), Ok(2.0));
+	assert_eq!(evaluate("modinv 3 11"), Ok(4.0));
+	assert_eq!(evaluate("modinv 2 4"), Err(Error::NoInverse));
+	assert_eq!(evaluate("gcd 1.5 3"), Err(Error::NonInteger));
+	assert_eq!(evaluate("isprime (2 ^ 60)"), Err(Error::InexactInteger));
+	assert_eq!(evaluate("powmod 2 10 0"), Err(Error::Domain));
+}
+
+#[test]
+fn test_statistics() {
+	assert_eq!(evaluate("mean [1, 2, 3, 4]"), Ok(2.5));
+	assert_eq!(evaluate("median [3, 1, 2]"), Ok(2.0));
+	assert_eq!(evaluate("median [4, 1, 3, 2]"), Ok(2.5));
+	assert_eq!(evaluate("var [2, 4, 4, 4, 5, 5, 7, 9]"), Ok(32.0 / 7.0));
+	assert_eq!(evaluate("stdev [1, 3]"), Ok(2f64.sqrt()));
+	assert_eq!(evaluate("sum [1, 2 * 3, -4]"), Ok(3.0));
+	assert_eq!(evaluate("prod [2, 3] + 1"), Ok(7.0));
+	assert_eq!(evaluate("min [3, -1, 2] max [1, 5]"), Err(Error::ExpectedOperator));
+	assert_eq!(evaluate("min [3, -1, 2]"), Ok(-1.0));
+	assert_eq!(evaluate("max [1, 5]"), Ok(5.0));
+	assert_eq!(evaluate("var [1]"), Err(Error::Domain));
+	assert_eq!(Statistic::Median.apply(&[]), Err(Error::Domain));
+	assert_eq!(Statistic::Sum.apply(&[]), Err(Error::Domain));
+	assert_eq!(evaluate("sum 1"), Err(Error::ExpectedList));
+	assert_eq!(evaluate("[1, 2] + 1"), Err(Error::DimensionMismatch));
+	assert_eq!(value("[1, (2 + 3)]"), Ok(Value::List(vec![1.0, 5.0])));
+	assert_eq!(evaluate("[1, ]"), Err(Error::EmptyBrackets));
+	assert_eq!(evaluate("1, 2"), Err(Error::MisplacedComma));
+}
+
+#[test]
+fn test_series() {
+	assert_eq!(evaluate("sum(k, 1, 10, k^2)"), Ok(385.0));
+	assert_eq!(evaluate("prod(k, 1, 5, k)"), Ok(120.0));
+	assert_eq!(evaluate("sum(k, 1, 3, prod(j, 1, k, j)) + 1"), Ok(10.0));
+	assert_eq!(evaluate("sum(k, 1, 2, sum(k, 1, k, k))"), Ok(4.0));
+	assert_eq!(evaluate("2 sum(k, 1, 2, k)"), Err(Error::ExpectedOperator));
+	assert_eq!(evaluate("sum(k, 1, 2, k) (2)"), Ok(6.0));
+	assert_eq!(evaluate("sum(k, 5, 1, k)"), Ok(0.0));
+	assert_eq!(evaluate("prod(k, 5, 1, k)"), Ok(1.0));
+	assert_eq!(evaluate("sum([1, 2])"), Ok(3.0));
+	assert_eq!(evaluate("sum(k, 1, 10)"), Err(Error::ExpectedArguments(4)));
+	assert_eq!(evaluate("sum(k, 1, 1.5, k)"), Err(Error::NonInteger));
+	assert_eq!(evaluate("sum(k, 1, 1e7, k)"), Err(Error::IterationLimit));
+	assert_eq!(evaluate("sum(k, 1, 2, j)"), Err(Error::InvalidItem));
+	assert_eq!(evaluate("k + 1"), Err(Error::InvalidItem));
+}
+
+#[test]
+fn test_calculus() {
+	let approximate = |expression: &str, value: f64|
+		(evaluate(expression).unwrap() - value).abs() < 1e-9;
+	assert!(approximate("integrate(x^2, 0, 3)", 9.0));
+	assert!(approximate("integrate(sin t, 0, pi)", 2.0));
+	assert!(approximate("integrate(2, 0, 3)", 6.0));
+	assert!(approximate("deriv(x^3, 2)", 12.0));
+	assert!(approximate("deriv(sin x, 0) + 1", 2.0));
+	assert!(approximate("sum(k, 1, 2, integrate(k * x, 0, 1))", 1.5));
+	assert!(matches!(value("integrate(x^2, 0, 3)"), Ok(Value::Estimate(_, error))
+		if error < 1e-9));
+
+	assert_eq!(evaluate("integrate(x, 0)"), Err(Error::ExpectedArguments(3)));
+	assert_eq!(evaluate("integrate(x y, 0, 1)"), Err(Error::InvalidItem));
+	assert_eq!(evaluate("integrate(ln x, -1, 1)"), Err(Error::Domain));
+}
+
+#[test]
+fn test_polynomial_roots() {
+	assert_eq!(value("roots [2, -4]"), Ok(Value::List(vec![2.0])));
+	assert_eq!(value("roots [1, -3, 2]"), Ok(Value::List(vec![1.0, 2.0])));
+	assert_eq!(value("roots [1, 2, 5]"), Ok(Value::ComplexList(vec![
+		Complex::new(-1.0, -2.0), Complex::new(-1.0, 2.0)])));
+	assert_eq!(evaluate("sum roots [1, -6, 11, -6]").map(f64::round), Ok(6.0));
+	assert_eq!(evaluate("roots [0, 0]"), Err(Error::Domain));
+	assert_eq!(evaluate("roots [4]"), Err(Error::Domain));
+	assert_eq!(evaluate("roots [0, 0, 1]"), Err(Error::Domain));
+	assert_eq!(evaluate("median roots [4]"), Err(Error::Domain));
+	assert_eq!(value("roots [1, 0, 0]"), Ok(Value::List(vec![0.0, 0.0])));
+	assert_eq!(evaluate("sum roots [1, 0, 1]"), Err(Error::ExpectedList));
+}
+
+#[test]
+fn test_matrices() {
+	let matrix = |rows: &[&[f64]]| Ok(Value::Matrix(Matrix::from_rows(rows.iter()
+		.map(|row| row.to_vec()).collect()).unwrap()));
+	assert_eq!(value("[[1, 2], [3, 4]] * [[0, 1], [1, 0]]"), matrix(&[&[2.0, 1.0], &[4.0, 3.0]]));
+	assert_eq!(value("[[1, 2], [3, 4]] .* [[0, 1], [1, 0]]"), matrix(&[&[0.0, 2.0], &[3.0, 0.0]]));
+	assert_eq!(value("[[1, 2], [3, 4]] ^ 2"), matrix(&[&[7.0, 10.0], &[15.0, 22.0]]));
+	assert_eq!(value("2 * [[1, 2]] - [[1, 1]]"), matrix(&[&[1.0, 3.0]]));
+	assert_eq!(value("transpose [[1, 2]]"), matrix(&[&[1.0], &[2.0]]));
+	assert_eq!(value("inv [[2, 0], [0, 4]]"), matrix(&[&[0.5, 0.0], &[0.0, 0.25]]));
+	assert_eq!(value("[[1, 2], [3, 4]] * [1, 1]"), Ok(Value::List(vec![3.0, 7.0])));
+	assert_eq!(value("linsolve [[2, 1], [1, 3]] [3, 5]"), Ok(Value::List(vec![0.8, 1.4])));
+	assert_eq!(evaluate("[1, 2] * [3, 4]"), Ok(11.0));
+	assert_eq!(evaluate("det [[0, 2], [1, 1]]"), Ok(-2.0));
+	assert_eq!(evaluate("[[1, 2], [3]]"), Err(Error::DimensionMismatch));
+	assert_eq!(evaluate("[[1, 2]] * [[1, 2]]"), Err(Error::DimensionMismatch));
+	assert_eq!(evaluate("[[1, 2]] ^ 2"), Err(Error::NonSquare));
+	assert_eq!(evaluate("[[1, 2], [2, 4]] ^ -1"), Err(Error::SingularMatrix));
+	assert_eq!(evaluate("det [1, 2]"), Err(Error::ExpectedMatrix));
+	assert_eq!(evaluate("[[1]] % 2"), Err(Error::ExpectedNumber));
+}
+
+#[test]
+fn test_comparisons() {
+	assert_eq!(evaluate("1 < 2"), Ok(1.0));
+	assert_eq!(evaluate("2 <= 1"), Ok(0.0));
+	assert_eq!(evaluate("1 + 1 == 2"), Ok(1.0));
+	assert_eq!(evaluate("3 != 3 or 2 >= 2 and 1 > 0"), Ok(1.0));
+	assert_eq!(evaluate("not 5 == 1"), Ok(0.0));
+	assert_eq!(evaluate("not (5 == 1)"), Ok(1.0));
+	assert_eq!(evaluate("0 and ln -1"), Ok(0.0));
+	assert_eq!(evaluate("1 or ln -1"), Ok(1.0));
+	assert_eq!(evaluate("[1] < 2"), Err(Error::ExpectedNumber));
+}
+
+#[test]
+fn test_conditionals() {
+	assert_eq!(evaluate("if 1 > 2 then 10 else 20"), Ok(20.0));
+	assert_eq!(evaluate("if 1 then 2 else 3 + 4"), Ok(2.0));
+	assert_eq!(evaluate("(if 0 then 2 else 3) + 4"), Ok(7.0));
+	assert_eq!(evaluate("if 0 then 1 else if 1 then 2 else 3"), Ok(2.0));
+	assert_eq!(evaluate("if 1 then if 0 then 1 else 2 else 3"), Ok(2.0));
+	assert_eq!(evaluate("if 0 then ln -1 else 1"), Ok(1.0));
+	assert_eq!(evaluate("sum(k, 1, 4, if k % 2 == 0 then k else 0)"), Ok(6.0));
+	assert_eq!(value("[if 0 then 1 else 2, 5]"), Ok(Value::List(vec![2.0, 5.0])));
+	assert_eq!(evaluate("if 1 then 2"), Err(Error::ExpectedKeyword("else")));
+	assert_eq!(evaluate("if 1 2 else 3"), Err(Error::ExpectedOperator));
+	assert_eq!(evaluate("if then 1 else 2"), Err(Error::ExpectedValued));
+	assert_eq!(evaluate("1 else 2"), Err(Error::MisplacedKeyword));
+}
+
+#[test]
+fn test_history_range() {
+	let context = &mut Context::default();
+	for number in [1.0, 2.0, 6.0] {
+		context.push_value(Value::Number(number));
+	}
+
+	let mut evaluate = |expression: &str| {
+		context.expression = expression.to_owned();
+		crate::interface::evaluate(context).map_err(|error| error.error.node)
+	};
+
+	assert_eq!(evaluate("sum $0..$2"), Ok(Value::Number(9.0)));
+	assert_eq!(evaluate("$1..$"), Ok(Value::List(vec![2.0, 6.0])));
+	assert_eq!(evaluate("mean $$$..$$"), Ok(Value::Number(1.5)));
+	assert_eq!(evaluate("sum $2..$0"), Err(Error::InvalidRange));
+	assert_eq!(evaluate("sum $0..$3"), Err(Error::UndefinedVariable("3".to_owned())));
+	assert_eq!(evaluate("sum 1..$3"), Err(Error::InvalidTerminal));
+}
+
+#[test]
+fn test_coalesce() {
+	assert_eq!(evaluate("10 - 2 + 4;"), Ok(4.0));
+	assert_eq!(evaluate("1 / 2 - 3 + 4; ;"), Ok(-0.2));
+	assert_eq!(evaluate("sqrt 4 + 5;"), Ok(3.0));
+	assert_eq!(evaluate("sqrt 4 + 5;;"), Ok(7.0));
+	assert_eq!(evaluate("2 * -3 + 4;"), Ok(-14.0));
+	assert_eq!(evaluate("2 * -3 + 4;;"), Ok(2.0));
+	assert_eq!(evaluate("ncr 5 2 + 1;"), Ok(10.0));
+	assert_eq!(evaluate("ncr 5 2 + 1;;"), Ok(11.0));
+	assert_eq!(evaluate("2 * ncr 5 2 + 1;;"), Ok(22.0));
+	assert_eq!(evaluate("ncr 9 sqrt 4 + 5;"), Ok(84.0));
+	assert_eq!(evaluate("ncr 9 sqrt 4 + 5;;"), Ok(36.0));
+	assert_eq!(evaluate("ncr 5 2;"), Ok(10.0));
+}
+
+#[test]
+fn test_explicit_coalesce() {
+	assert_eq!(evaluate("1 / 1 + 2 + 3;3"), Ok(1.0 / 6.0));
+	assert_eq!(evaluate("10 - 2 + 4;2"), Ok(4.0));
+	assert_eq!(evaluate("1 / 2 - 3 + 4;2;2"), Ok(-0.2));
+	assert_eq!(evaluate("1 + 2;1"), Err(Error::InvalidCoalesce));
+}
+
+#[test]
+fn test_invalid_coalesce() {
+	assert_eq!(evaluate("1 + 2 * ; 3"), Err(Error::InvalidCoalesce));
+	assert_eq!(evaluate("ncr 5 2;;"), Err(Error::InvalidCoalesce));
+	assert_eq!(evaluate("1 + 2;4"), Err(Error::InvalidCoalesce));
+}
+
+#[test]
+fn test_domain() {
+	assert_eq!(evaluate("ln -1"), Err(Error::Domain));
+	assert_eq!(evaluate("log2 0"), Err(Error::Domain));
+	assert_eq!(evaluate("asin 2"), Err(Error::Domain));
+	assert_eq!(evaluate("(-8) ^ 0.5"), Err(Error::Domain));
+	assert_eq!(evaluate("0 ^ -1"), Err(Error::ZeroDivision));
+	assert_eq!(evaluate("1 % 0"), Err(Error::ZeroDivision));
+	assert_eq!(evaluate("10 ^ 400"), Err(Error::Overflow));
+	assert_eq!(evaluate("1e300 * 1e300"), Err(Error::Overflow));
+}
+
+#[test]
+fn test_ieee() {
+	let context = &mut Context::default();
+	context.ieee = true;
+	context.expression = "1 / 0".to_owned();
+	assert_eq!(crate::interface::evaluate(context).ok(), Some(Value::Number(f64::INFINITY)));
+	context.expression = "ln -1".to_owned();
+	assert!(matches!(crate::interface::evaluate(context), Ok(Value::Number(number))
+		if number.is_nan()));
+}
+
+#[test]
+fn test_spans() {
+	let spans = |expression: &str| {
+		let context = &mut Context::default();
+		context.expression = expression.to_owned();
+		let error = crate::interface::evaluate(context).unwrap_err();
+		let secondary = error.secondary.unwrap();
+		let Span(byte_start, byte_end) = error.error.span;
+		let Span(secondary_start, secondary_end) = secondary.span;
+		((byte_start, byte_end), (secondary_start, secondary_end), secondary.node)
+	};
+
+	assert_eq!(spans("sqrt -4"), ((0, 4), (5, 7), "-4".to_owned()));
+	assert_eq!(spans("1 / (2 - 2)"), ((2, 3), (5, 10), "0".to_owned()));
+	assert_eq!(spans("[[1, 2]] * [1]"), ((9, 10), (0, 14), "1×2 matrix and 1-vector".to_owned()));
+}
+
 fn evaluate(expression: &str) -> Result<f64, Error> {
-	let mut context = &mut Context::default();
+	match value(expression)? {
+		Value::Number(number) | Value::Estimate(number, _) => Ok(number),
+		_ => Err(Error::ExpectedNumber),
+	}
+}
+
+fn value(expression: &str) -> Result<Value, Error> {
+	let context = &mut Context::default();
 	context.expression = expression.to_owned();
 	crate::interface::evaluate(context)
-		.map_err(|error| error.node)
+		.map_err(|error| error.error.node)
 }
@@ -1 +1,11 @@
 mod evaluation;
+
+use crate::node::Node;
+use crate::span::Spanned;
+
+/// Parses an expression in which the given identifiers are bound.
+pub fn parse(expression: &str, bound: &[&str]) -> Spanned<Node> {
+	let lexer = &mut crate::lexer::Lexer::new(expression);
+	let bound: Vec<_> = bound.iter().map(|identifier| identifier.to_string()).collect();
+	crate::parse::parse_root(crate::coalesce::coalesce_bound(lexer, &bound).unwrap())
+}
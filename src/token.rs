@@ -4,11 +4,21 @@ use crate::item::{Constant, Function};
 pub enum Token {
 	Terminal(f64),
 	Variable(String),
+	Identifier(String),
 	Operator(Operator),
 	Function(Function),
 	Constant(Constant),
 	ParenthesisOpen,
 	ParenthesisClose,
+	BracketOpen,
+	BracketClose,
+	Comma,
+	Range,
+	If,
+	Then,
+	Else,
+	Factorial,
+	/// Combines one more than the given number of terms.
 	Coalesce(usize),
 }
 
@@ -17,17 +27,132 @@ pub enum Operator {
 	Add,
 	Minus,
 	Multiply,
+	/// The product of corresponding elements of lists or matrices.
+	ElementMultiply,
 	Divide,
 	Modulo,
 	Power,
+	Less,
+	LessEqual,
+	Greater,
+	GreaterEqual,
+	Equal,
+	NotEqual,
+	And,
+	Or,
 }
 
 impl Operator {
+	pub fn definition(&self) -> &'static Definition {
+		OPERATORS.iter().find(|definition| definition.operator == *self).unwrap()
+	}
+
 	pub fn precedence(&self) -> usize {
-		match self {
-			Operator::Add | Operator::Minus => 0,
-			Operator::Multiply | Operator::Divide | Operator::Modulo => 1,
-			Operator::Power => 2,
+		self.definition().precedence
+	}
+
+	pub fn associativity(&self) -> Associativity {
+		self.definition().associativity
+	}
+
+	/// Finds the operator with the longest symbol of punctuation
+	/// at the start of a string.
+	pub fn symbol(string: &str) -> Option<Operator> {
+		OPERATORS.iter().filter(|definition| !definition.is_word())
+			.filter(|definition| string.starts_with(definition.symbol))
+			.max_by_key(|definition| definition.symbol.len())
+			.map(|definition| definition.operator)
+	}
+
+	/// Finds the operator that is written as a word.
+	pub fn word(string: &str) -> Option<Operator> {
+		OPERATORS.iter().find(|definition| definition.is_word() && definition.symbol == string)
+			.map(|definition| definition.operator)
+	}
+}
+
+/// How a sequence of operators with the same precedence is grouped.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Associativity {
+	Left,
+	Right,
+}
+
+/// An operand of a binary operator.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Operand {
+	Left,
+	Right,
+}
+
+/// The definition of a binary operator. Operators are lexed from their
+/// symbols, parsed by their precedence and associativity and evaluated
+/// on numbers by their implementation.
+pub struct Definition {
+	pub operator: Operator,
+	pub symbol: &'static str,
+	pub precedence: usize,
+	pub associativity: Associativity,
+	pub apply: fn(f64, f64) -> f64,
+	/// Finds the operand that causes a division by zero.
+	pub zero_division: fn(f64, f64) -> Option<Operand>,
+}
+
+impl Definition {
+	const fn new(operator: Operator, symbol: &'static str, precedence: usize,
+	             apply: fn(f64, f64) -> f64) -> Definition {
+		Definition {
+			operator,
+			symbol,
+			precedence,
+			associativity: Associativity::Left,
+			apply,
+			zero_division: |_, _| None,
 		}
 	}
+
+	const fn right(self) -> Definition {
+		Definition { associativity: Associativity::Right, ..self }
+	}
+
+	const fn divides(self, zero_division: fn(f64, f64) -> Option<Operand>) -> Definition {
+		Definition { zero_division, ..self }
+	}
+
+	fn is_word(&self) -> bool {
+		self.symbol.chars().all(char::is_alphabetic)
+	}
+}
+
+/// The precedence of a unary minus. A unary minus binds tighter than
+/// multiplication and looser than exponentiation so that `-2^2` is `-4`.
+pub const NEGATION_PRECEDENCE: usize = 5;
+
+pub const OPERATORS: &[Definition] = &[
+	Definition::new(Operator::Or, "or", 0, |left, right| boolean(left != 0.0 || right != 0.0)),
+	Definition::new(Operator::And, "and", 1, |left, right| boolean(left != 0.0 && right != 0.0)),
+	Definition::new(Operator::Less, "<", 2, |left, right| boolean(left < right)),
+	Definition::new(Operator::LessEqual, "<=", 2, |left, right| boolean(left <= right)),
+	Definition::new(Operator::Greater, ">", 2, |left, right| boolean(left > right)),
+	Definition::new(Operator::GreaterEqual, ">=", 2, |left, right| boolean(left >= right)),
+	Definition::new(Operator::Equal, "==", 2, |left, right| boolean(left == right)),
+	Definition::new(Operator::NotEqual, "!=", 2, |left, right| boolean(left != right)),
+	Definition::new(Operator::Add, "+", 3, |left, right| left + right),
+	Definition::new(Operator::Minus, "-", 3, |left, right| left - right),
+	Definition::new(Operator::Multiply, "*", 4, |left, right| left * right),
+	Definition::new(Operator::ElementMultiply, ".*", 4, |left, right| left * right),
+	Definition::new(Operator::Divide, "/", 4, |left, right| left / right)
+		.divides(|_, right| (right == 0.0).then_some(Operand::Right)),
+	Definition::new(Operator::Modulo, "%", 4, |left, right| left % right)
+		.divides(|_, right| (right == 0.0).then_some(Operand::Right)),
+	Definition::new(Operator::Power, "^", 6, f64::powf).right()
+		.divides(|left, right| (left == 0.0 && right < 0.0).then_some(Operand::Left)),
+];
+
+/// Converts the truth of a comparison into one or zero.
+pub fn boolean(truth: bool) -> f64 {
+	match truth {
+		true => 1.0,
+		false => 0.0,
+	}
 }
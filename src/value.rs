@@ -0,0 +1,50 @@
+use std::fmt;
+
+use crate::complex::Complex;
+use crate::matrix::Matrix;
+
+/// The result of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	Number(f64),
+	List(Vec<f64>),
+	/// A number that is approximated with an estimate of its error.
+	Estimate(f64, f64),
+	/// A list of numbers in which some numbers are not real.
+	ComplexList(Vec<Complex>),
+	Matrix(Matrix),
+}
+
+impl Value {
+	/// Describes the dimensions of a value.
+	pub fn shape(&self) -> String {
+		match self {
+			Value::Number(_) | Value::Estimate(_, _) => "number".to_owned(),
+			Value::List(list) => format!("{}-vector", list.len()),
+			Value::ComplexList(list) => format!("{}-vector", list.len()),
+			Value::Matrix(matrix) => matrix.shape(),
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Value::Number(number) | Value::Estimate(number, _) => write!(f, "{}", number),
+			Value::List(list) => self::list(f, list),
+			Value::ComplexList(list) => self::list(f, list),
+			Value::Matrix(matrix) => write!(f, "{}", matrix),
+		}
+	}
+}
+
+fn list(f: &mut fmt::Formatter, list: &[impl fmt::Display]) -> fmt::Result {
+	write!(f, "[")?;
+	for (index, element) in list.iter().enumerate() {
+		match index {
+			0 => write!(f, "{}", element),
+			_ => write!(f, ", {}", element),
+		}?;
+	}
+	write!(f, "]")
+}